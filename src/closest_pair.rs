@@ -0,0 +1,140 @@
+//! Closest pair of points in the plane.
+
+use std::cmp::Ordering;
+
+
+/// A point in the plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Point {
+        Point { x: x, y: y }
+    }
+
+    /// Euclidean distance.
+    pub fn distance(&self, other: &Point) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+fn cmp_f64(a: f64, b: f64) -> Ordering {
+    a.partial_cmp(&b).expect("NaN coordinate")
+}
+
+
+/// Closest pair of points and their distance, by the O(n log n) divide and
+/// conquer algorithm. None for less than two points, panics on NaN.
+pub fn closest_pair(points: &[Point]) -> Option<(Point, Point, f64)> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let mut px = points.to_vec();
+    px.sort_by(|a, b| cmp_f64(a.x, b.x).then(cmp_f64(a.y, b.y)));
+    // indices into px, ordered by y
+    let mut py: Vec<usize> = (0..px.len()).collect();
+    py.sort_by(|&i, &j| cmp_f64(px[i].y, px[j].y));
+
+    let (i, j, d) = closest_split(&px, 0, px.len(), &py);
+    Some((px[i], px[j], d))
+}
+
+/// Closest pair of points by checking all pairs in O(n^2), for reference.
+pub fn closest_pair_brute_force(points: &[Point]) -> Option<(Point, Point, f64)> {
+    let mut best = None;
+    for i in 0..points.len() {
+        for j in i + 1..points.len() {
+            let d = points[i].distance(&points[j]);
+            if best.map_or(true, |(_, _, bd)| d < bd) {
+                best = Some((points[i], points[j], d));
+            }
+        }
+    }
+    best
+}
+
+// Closest pair among px[lo..hi], `py` holds the same indices sorted by y.
+// Returns indices into px and the distance.
+fn closest_split(px: &[Point], lo: usize, hi: usize, py: &[usize]) -> (usize, usize, f64) {
+    let n = hi - lo;
+    if n <= 3 {
+        let mut best = (lo, lo + 1, px[lo].distance(&px[lo + 1]));
+        for i in lo..hi {
+            for j in i + 1..hi {
+                let d = px[i].distance(&px[j]);
+                if d < best.2 {
+                    best = (i, j, d);
+                }
+            }
+        }
+        return best;
+    }
+
+    let mid = lo + n / 2;
+    let mid_x = px[mid].x;
+    let (left_y, right_y): (Vec<usize>, Vec<usize>) = py.iter().partition(|&&i| i < mid);
+
+    let left = closest_split(px, lo, mid, &left_y);
+    let right = closest_split(px, mid, hi, &right_y);
+    let mut best = if left.2 <= right.2 { left } else { right };
+
+    // only points within best distance of the dividing line can do better,
+    // and each needs to be checked against at most 7 successors by y
+    let strip: Vec<usize> = py.iter()
+                              .cloned()
+                              .filter(|&i| (px[i].x - mid_x).abs() < best.2)
+                              .collect();
+    for (k, &i) in strip.iter().enumerate() {
+        for &j in strip[k + 1..].iter().take(7) {
+            if px[j].y - px[i].y >= best.2 {
+                break;
+            }
+            let d = px[i].distance(&px[j]);
+            if d < best.2 {
+                best = (i, j, d);
+            }
+        }
+    }
+    best
+}
+
+
+#[cfg(test)]
+mod tests {
+    use self::super::*;
+
+    #[test]
+    fn test_closest_pair() {
+        let points = vec![Point::new(2.0, 3.0),
+                          Point::new(12.0, 30.0),
+                          Point::new(40.0, 50.0),
+                          Point::new(5.0, 1.0),
+                          Point::new(12.0, 10.0),
+                          Point::new(3.0, 4.0)];
+        let (p, q, d) = closest_pair(&points).unwrap();
+        assert_eq!(d, 2.0_f64.sqrt());
+        assert!((p, q) == (points[0], points[5]) || (p, q) == (points[5], points[0]));
+
+        assert_eq!(closest_pair(&points[..1]), None);
+        assert_eq!(closest_pair(&[Point::new(1.0, 1.0), Point::new(1.0, 1.0)]).unwrap().2,
+                   0.0);
+    }
+
+    #[test]
+    fn test_closest_pair_against_brute_force() {
+        for n in 2..200 {
+            let points: Vec<Point> = (0..n)
+                                         .map(|i| {
+                                             Point::new(((i * 7919) % 1009) as f64,
+                                                        ((i * 104729) % 997) as f64 / 3.0)
+                                         })
+                                         .collect();
+            assert_eq!(closest_pair(&points).unwrap().2,
+                       closest_pair_brute_force(&points).unwrap().2);
+        }
+    }
+}
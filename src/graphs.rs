@@ -1,3 +1,4 @@
+use std::cmp;
 use std::collections::vec_deque::VecDeque;
 
 
@@ -123,6 +124,14 @@ impl Digraph {
     pub fn kosaraju_sharir_scc(&self) -> KosarajuSharirSCC {
         KosarajuSharirSCC::new(self)
     }
+
+    pub fn tarjan_scc(&self) -> TarjanSCC {
+        TarjanSCC::new(self)
+    }
+
+    pub fn gabow_scc(&self) -> GabowSCC {
+        GabowSCC::new(self)
+    }
 }
 
 pub enum SearchSource {
@@ -170,12 +179,25 @@ impl<'a> SearchPaths<'a> {
         }
     }
 
+    // DFS, non-recursive. Visits vertices in the same order as the recursive
+    // version, so edge_to is the same DFS tree.
     fn dfs_from(&mut self, v: usize) {
         self.marked[v] = true;
-        for &w in self.graph.adj(v) {
-            if !self.marked[w] {
-                self.dfs_from(w);
-                self.edge_to[w] = Some(v);
+        // vertices on the current path, with the next adjacency index to scan
+        let mut stack = vec![(v, 0)];
+        while let Some(&mut (v, ref mut next)) = stack.last_mut() {
+            let adj = self.graph.adj(v);
+            match adj[*next..].iter().position(|&w| !self.marked[w]) {
+                Some(i) => {
+                    let w = adj[*next + i];
+                    *next += i + 1;
+                    self.marked[w] = true;
+                    self.edge_to[w] = Some(v);
+                    stack.push((w, 0));
+                }
+                None => {
+                    stack.pop();
+                }
             }
         }
     }
@@ -298,6 +320,31 @@ impl<'a> DepthFirstOrder<'a> {
     }
 }
 
+/// Strongly-connected components of a digraph, with ids from 0 to count - 1.
+pub trait SCC {
+    /// Number of vertices of the digraph.
+    fn v(&self) -> usize;
+
+    /// Number of strongly-connected components.
+    fn count(&self) -> usize;
+
+    /// Component id of vertex v.
+    fn id(&self, v: usize) -> usize;
+
+    fn connected(&self, v: usize, w: usize) -> bool {
+        self.id(v) == self.id(w)
+    }
+
+    /// Vertices of each component, indexed by component id.
+    fn components(&self) -> Vec<Vec<usize>> {
+        let mut components = vec![vec![]; self.count()];
+        for v in 0..self.v() {
+            components[self.id(v)].push(v);
+        }
+        components
+    }
+}
+
 /// Compute the strongly-connected components of a digraph using the
 /// Kosaraju-Sharir algorithm.
 pub struct KosarajuSharirSCC<'a> {
@@ -373,6 +420,203 @@ impl<'a> KosarajuSharirSCC<'a> {
     }
 }
 
+impl<'a> SCC for KosarajuSharirSCC<'a> {
+    fn v(&self) -> usize {
+        self.id.len()
+    }
+
+    fn count(&self) -> usize {
+        KosarajuSharirSCC::count(self)
+    }
+
+    fn id(&self, v: usize) -> usize {
+        KosarajuSharirSCC::id(self, v)
+    }
+
+    fn connected(&self, v: usize, w: usize) -> bool {
+        KosarajuSharirSCC::connected(self, v, w)
+    }
+}
+
+/// Compute the strongly-connected components of a digraph in a single DFS
+/// using Tarjan's algorithm.
+pub struct TarjanSCC {
+    id: Vec<usize>,
+    count: usize,
+}
+
+impl TarjanSCC {
+    fn new(graph: &Digraph) -> TarjanSCC {
+        let n = graph.v();
+        // preorder number of v
+        let mut pre: Vec<Option<usize>> = vec![None; n];
+        // lowest preorder number reachable from v's subtree through the stack
+        let mut low = vec![0; n];
+        let mut id: Vec<Option<usize>> = vec![None; n];
+        let mut pre_counter = 0;
+        let mut count = 0;
+        // visited vertices not yet assigned to a component
+        let mut stack = vec![];
+
+        for s in 0..n {
+            if pre[s].is_some() {
+                continue;
+            }
+            pre[s] = Some(pre_counter);
+            low[s] = pre_counter;
+            pre_counter += 1;
+            stack.push(s);
+
+            // DFS, non-recursive: vertices on the current path, with the next
+            // adjacency index to scan
+            let mut path = vec![(s, 0)];
+            while let Some(&mut (v, ref mut next)) = path.last_mut() {
+                if let Some(&w) = graph.adj(v).get(*next) {
+                    *next += 1;
+                    match pre[w] {
+                        None => {
+                            pre[w] = Some(pre_counter);
+                            low[w] = pre_counter;
+                            pre_counter += 1;
+                            stack.push(w);
+                            path.push((w, 0));
+                        }
+                        // w is still on the stack
+                        Some(pre_w) if id[w].is_none() => low[v] = cmp::min(low[v], pre_w),
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                path.pop();
+                if let Some(&(u, _)) = path.last() {
+                    low[u] = cmp::min(low[u], low[v]);
+                }
+                if Some(low[v]) == pre[v] {
+                    // v is the root of a component
+                    loop {
+                        let w = stack.pop().unwrap();
+                        id[w] = Some(count);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    count += 1;
+                }
+            }
+        }
+
+        TarjanSCC {
+            id: id.into_iter().map(|i| i.unwrap()).collect(),
+            count: count,
+        }
+    }
+}
+
+impl SCC for TarjanSCC {
+    fn v(&self) -> usize {
+        self.id.len()
+    }
+
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn id(&self, v: usize) -> usize {
+        self.id[v]
+    }
+}
+
+/// Compute the strongly-connected components of a digraph in a single DFS
+/// using Gabow's path-based algorithm.
+pub struct GabowSCC {
+    id: Vec<usize>,
+    count: usize,
+}
+
+impl GabowSCC {
+    fn new(graph: &Digraph) -> GabowSCC {
+        let n = graph.v();
+        // preorder number of v
+        let mut pre: Vec<Option<usize>> = vec![None; n];
+        let mut id: Vec<Option<usize>> = vec![None; n];
+        let mut pre_counter = 0;
+        let mut count = 0;
+        // visited vertices not yet assigned to a component
+        let mut stack = vec![];
+        // roots of the components not yet assigned, a subsequence of stack
+        let mut roots: Vec<usize> = vec![];
+
+        for s in 0..n {
+            if pre[s].is_some() {
+                continue;
+            }
+            pre[s] = Some(pre_counter);
+            pre_counter += 1;
+            stack.push(s);
+            roots.push(s);
+
+            // DFS, non-recursive: vertices on the current path, with the next
+            // adjacency index to scan
+            let mut path = vec![(s, 0)];
+            while let Some(&mut (v, ref mut next)) = path.last_mut() {
+                if let Some(&w) = graph.adj(v).get(*next) {
+                    *next += 1;
+                    match pre[w] {
+                        None => {
+                            pre[w] = Some(pre_counter);
+                            pre_counter += 1;
+                            stack.push(w);
+                            roots.push(w);
+                            path.push((w, 0));
+                        }
+                        // w is still on the stack, contract the cycle through it
+                        Some(_) if id[w].is_none() => {
+                            while pre[*roots.last().unwrap()] > pre[w] {
+                                roots.pop();
+                            }
+                        }
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                path.pop();
+                if roots.last() == Some(&v) {
+                    roots.pop();
+                    loop {
+                        let w = stack.pop().unwrap();
+                        id[w] = Some(count);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    count += 1;
+                }
+            }
+        }
+
+        GabowSCC {
+            id: id.into_iter().map(|i| i.unwrap()).collect(),
+            count: count,
+        }
+    }
+}
+
+impl SCC for GabowSCC {
+    fn v(&self) -> usize {
+        self.id.len()
+    }
+
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn id(&self, v: usize) -> usize {
+        self.id[v]
+    }
+}
+
 pub fn read_graph_from_string(s: &str) -> Digraph {
     let mut g = Digraph::new(1);
 
@@ -452,3 +696,92 @@ fn test_depth_first_order() {
     assert!(ord == vec![8, 7, 2, 3, 0, 6, 9, 10, 11, 12, 1, 5, 4] ||
             ord == vec![8, 7, 2, 3, 0, 5, 1, 6, 9, 11, 10, 12, 4]);
 }
+
+
+#[test]
+fn test_dfs_paths() {
+    let mut g = Digraph::new(4);
+    g.add_edge(0, 1);
+    g.add_edge(0, 2);
+    g.add_edge(1, 2);
+    g.add_edge(3, 0);
+
+    // 2 is reached through 1, as 0 -> 1 is explored first
+    let paths = g.dfs(0);
+    assert_eq!(paths.path_to(2), Some(vec![2, 1, 0]));
+    assert_eq!(paths.path_to(0), Some(vec![0]));
+    assert!(!paths.has_path_to(3));
+
+    let paths = g.dfs_multi_source(vec![3, 1]);
+    assert_eq!(paths.path_to(2), Some(vec![2, 0, 3]));
+    assert_eq!(paths.path_to(1), Some(vec![1]));
+}
+
+#[test]
+fn test_dfs_long_path() {
+    // deep enough to overflow the stack with a recursive DFS
+    let n = 1_000_000;
+    let mut g = Digraph::new(n);
+    for v in 0..n - 1 {
+        g.add_edge(v, v + 1);
+    }
+    let paths = g.dfs(0);
+    let path = paths.path_to(n - 1).unwrap();
+    assert_eq!(path.len(), n);
+    assert_eq!(path[0], n - 1);
+    assert_eq!(path[n - 1], 0);
+}
+
+#[cfg(test)]
+fn sorted_components<S: SCC>(scc: &S) -> Vec<Vec<usize>> {
+    let mut components = scc.components();
+    components.sort();
+    components
+}
+
+#[test]
+fn test_scc_algorithms_agree() {
+    let case4 = "1 2\n2 3\n2 4\n2 5\n3 6\n4 5\n4 7\n5 2\n5 6\n5 7\n6 3\n6 8\n7 8\n7 10\n8 7\n9 \
+                 7\n10 9\n10 11\n11 12\n12 10";
+    let g = read_graph_from_string(case4);
+    let kosaraju = g.kosaraju_sharir_scc();
+    let tarjan = g.tarjan_scc();
+    let gabow = g.gabow_scc();
+    assert_eq!(tarjan.count(), 4);
+    assert_eq!(gabow.count(), 4);
+    assert_eq!(sorted_components(&tarjan),
+               vec![vec![0], vec![1, 3, 4], vec![2, 5], vec![6, 7, 8, 9, 10, 11]]);
+    assert_eq!(sorted_components(&gabow), sorted_components(&tarjan));
+    assert_eq!(sorted_components(&kosaraju), sorted_components(&tarjan));
+    assert_eq!(kosaraju.count(), 4);
+    assert!(kosaraju.connected(6, 11));
+    assert!(tarjan.connected(6, 11));
+    assert!(!gabow.connected(0, 1));
+
+    // a pseudo-random sparse digraph
+    let n = 500;
+    let mut g = Digraph::new(n);
+    let mut x: u64 = 42;
+    for _ in 0..2 * n {
+        x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let u = (x >> 33) as usize % n;
+        x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let v = (x >> 33) as usize % n;
+        g.add_edge(u, v);
+    }
+    let expected = sorted_components(&g.kosaraju_sharir_scc());
+    assert!(expected.len() > 1 && expected.len() < n);
+    assert_eq!(sorted_components(&g.tarjan_scc()), expected);
+    assert_eq!(sorted_components(&g.gabow_scc()), expected);
+}
+
+#[test]
+fn test_scc_long_cycle() {
+    let n = 1_000_000;
+    let mut g = Digraph::new(n);
+    for v in 0..n {
+        g.add_edge(v, (v + 1) % n);
+    }
+    assert_eq!(g.tarjan_scc().count(), 1);
+    assert_eq!(g.gabow_scc().count(), 1);
+}
@@ -136,3 +136,64 @@ fn test_median_maintainer() {
     assert_eq!(mm.pop_median(), Some(1));
     assert_eq!(mm.pop_median(), Some(9));
 }
+
+
+/// In-place heap sort, returns the number of comparisons.
+pub fn heap_sort<T: Ord>(arr: &mut [T]) -> usize {
+    heap_sort_by(arr, |a, b| a.cmp(b))
+}
+
+pub fn heap_sort_by<T, F>(arr: &mut [T], mut compare: F) -> usize
+    where F: FnMut(&T, &T) -> cmp::Ordering
+{
+    let mut cnt = 0;
+    let n = arr.len();
+    {
+        let mut less = |a: &T, b: &T| {
+            cnt += 1;
+            compare(a, b) == cmp::Ordering::Less
+        };
+
+        // build a max-heap, then move the max to the end one at a time
+        for root in (0..n / 2).rev() {
+            sift_down(arr, root, n, &mut less);
+        }
+        for end in (1..n).rev() {
+            arr.swap(0, end);
+            sift_down(arr, 0, end, &mut less);
+        }
+    }
+    cnt
+}
+
+// max-heap over arr[..end], 0-indexed: children of k are 2k+1 and 2k+2
+fn sift_down<T, F>(arr: &mut [T], mut k: usize, end: usize, less: &mut F)
+    where F: FnMut(&T, &T) -> bool
+{
+    while 2 * k + 1 < end {
+        let mut j = 2 * k + 1;
+        if j + 1 < end && less(&arr[j], &arr[j + 1]) {
+            j += 1;
+        }
+        if !less(&arr[k], &arr[j]) {
+            break;
+        }
+        arr.swap(k, j);
+        k = j;
+    }
+}
+
+
+#[test]
+fn test_heap_sort() {
+    let mut v = vec![3, 8, 2, 5, 1, 4, 7, 6, 4];
+    assert!(heap_sort(&mut v[..]) > 0);
+    assert_eq!(&v, &[1, 2, 3, 4, 4, 5, 6, 7, 8]);
+
+    let mut v = vec!["b", "c", "a"];
+    heap_sort_by(&mut v[..], |a, b| b.cmp(a));
+    assert_eq!(&v, &["c", "b", "a"]);
+
+    let mut v: Vec<i32> = vec![];
+    assert_eq!(heap_sort(&mut v[..]), 0);
+}
@@ -0,0 +1,87 @@
+//! Instrumentation of the sorting algorithms.
+
+use std::collections::btree_map::BTreeMap;
+
+
+/// Receives the events of an instrumented sort. Every method defaults to a
+/// no-op, `()` is the observer that ignores everything.
+pub trait SortObserver {
+    /// Two items were compared.
+    fn on_compare(&mut self) {}
+
+    /// Two items were swapped, or one item was moved by a merge.
+    fn on_swap(&mut self) {}
+
+    /// A recursive call on a non-trivial subarray, the outermost call is depth 1.
+    fn on_recurse(&mut self, _depth: usize) {}
+
+    /// A subarray of length `len` is about to be partitioned or merged.
+    fn on_partition(&mut self, _len: usize) {}
+}
+
+impl SortObserver for () {}
+
+
+/// Statistics of one sort run.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SortStats {
+    /// Exact number of comparisons.
+    pub comparisons: u64,
+    /// Swaps, or element moves for merge based sorts.
+    pub swaps: u64,
+    /// Deepest recursion level reached.
+    pub max_depth: usize,
+    /// Histogram of partitioned (or merged) subarray lengths, bucket `k`
+    /// counts lengths in `2^k .. 2^(k+1)`.
+    pub partition_sizes: BTreeMap<usize, usize>,
+}
+
+impl SortStats {
+    pub fn new() -> SortStats {
+        Default::default()
+    }
+}
+
+impl SortObserver for SortStats {
+    fn on_compare(&mut self) {
+        self.comparisons += 1;
+    }
+
+    fn on_swap(&mut self) {
+        self.swaps += 1;
+    }
+
+    fn on_recurse(&mut self, depth: usize) {
+        if depth > self.max_depth {
+            self.max_depth = depth;
+        }
+    }
+
+    fn on_partition(&mut self, len: usize) {
+        let mut bucket = 0;
+        while len >> (bucket + 1) > 0 {
+            bucket += 1;
+        }
+        *self.partition_sizes.entry(bucket).or_insert(0) += 1;
+    }
+}
+
+
+#[test]
+fn test_sort_stats() {
+    let mut stats = SortStats::new();
+    stats.on_compare();
+    stats.on_compare();
+    stats.on_swap();
+    stats.on_recurse(3);
+    stats.on_recurse(2);
+    for &len in &[1, 2, 3, 4, 1000] {
+        stats.on_partition(len);
+    }
+
+    assert_eq!(stats.comparisons, 2);
+    assert_eq!(stats.swaps, 1);
+    assert_eq!(stats.max_depth, 3);
+    assert_eq!(stats.partition_sizes.into_iter().collect::<Vec<_>>(),
+               vec![(0, 1), (1, 2), (2, 1), (9, 1)]);
+}
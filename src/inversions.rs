@@ -1,51 +1,430 @@
+use std::cmp;
+use std::collections::btree_map::BTreeMap;
+use std::collections::btree_set::BTreeSet;
+
+use super::instrument::SortObserver;
+
+
 pub fn merge_sort_and_count_inversions(seq: &mut [usize]) -> usize {
-    if seq.len() <= 1 {
+    merge_sort_observed(seq, &mut ()) as usize
+}
+
+/// Sorts `seq` and returns its number of inversions.
+pub fn count_inversions<T: Ord>(seq: &mut [T]) -> u64 {
+    // merge sort references tagged with their position, so that T needs no
+    // Clone, then move the items into the sorted order
+    let (inv_cnt, order) = {
+        let mut keyed: Vec<(&T, usize)> = seq.iter().zip(0..).collect();
+        let inv_cnt = merge_sort_observed(&mut keyed[..], &mut ());
+        (inv_cnt, keyed.into_iter().map(|(_, i)| i).collect())
+    };
+    permute(seq, order);
+    inv_cnt
+}
+
+// Rearranges `seq` in place so that seq[i] becomes the old seq[order[i]].
+fn permute<T>(seq: &mut [T], mut order: Vec<usize>) {
+    for i in 0..seq.len() {
+        // walk the cycle through i, marking its positions done
+        let mut j = i;
+        loop {
+            let k = order[j];
+            order[j] = j;
+            if k == i {
+                break;
+            }
+            seq.swap(j, k);
+            j = k;
+        }
+    }
+}
+
+/// Returns the number of inversions in `seq`, leaving it untouched.
+pub fn count_inversions_of<T: Ord>(seq: &[T]) -> u64 {
+    let mut refs: Vec<&T> = seq.iter().collect();
+    count_inversions(&mut refs[..])
+}
+
+/// Merge sort counting inversions, reporting comparisons, element moves,
+/// recursion depth and merged subarray sizes to `observer`.
+///
+/// Allocates one scratch copy of `seq` up front, then merges back and forth
+/// between the two buffers.
+pub fn merge_sort_observed<T, O>(seq: &mut [T], observer: &mut O) -> u64
+    where T: Ord + Clone,
+          O: SortObserver
+{
+    let mut scratch = seq.to_vec();
+    sort_into(&mut scratch[..], seq, observer, 1)
+}
+
+// Sorts `dst`, with `src` holding the same items on entry. The halves are
+// sorted in `src`, using `dst` as scratch, then merged into `dst`.
+fn sort_into<T, O>(src: &mut [T], dst: &mut [T], observer: &mut O, depth: usize) -> u64
+    where T: Ord + Clone,
+          O: SortObserver
+{
+    if dst.len() <= 1 {
         0
     } else {
-        let mid = seq.len() / 2;
-        let x = merge_sort_and_count_inversions(&mut seq[..mid]);
-        let y = merge_sort_and_count_inversions(&mut seq[mid..]);
-        let z = merge(&mut seq[..], mid);
+        observer.on_recurse(depth);
+        let mid = dst.len() / 2;
+        let x = sort_into(&mut dst[..mid], &mut src[..mid], observer, depth + 1);
+        let y = sort_into(&mut dst[mid..], &mut src[mid..], observer, depth + 1);
+        observer.on_partition(dst.len());
+        let z = merge(src, mid, dst, observer);
 
         x + y + z
     }
 }
 
+/// Bottom-up (iterative) merge sort counting inversions, sorts `seq`.
+///
+/// Merges runs of width 1, 2, 4, ... between `seq` and one scratch buffer,
+/// the result is identical to `count_inversions`.
+pub fn count_inversions_bottom_up<T: Ord + Clone>(seq: &mut [T]) -> u64 {
+    let n = seq.len();
+    let mut scratch = seq.to_vec();
+    let mut inv_cnt = 0;
+    // whether the sorted runs currently live in `seq`
+    let mut in_seq = true;
+    let mut width = 1;
+
+    while width < n {
+        {
+            let (src, dst) = if in_seq {
+                (&*seq, &mut scratch[..])
+            } else {
+                (&scratch[..], &mut *seq)
+            };
+            let mut lo = 0;
+            while lo < n {
+                let mid = cmp::min(lo + width, n);
+                let hi = cmp::min(lo + 2 * width, n);
+                inv_cnt += merge(&src[lo..hi], mid - lo, &mut dst[lo..hi], &mut ());
+                lo = hi;
+            }
+        }
+        in_seq = !in_seq;
+        width *= 2;
+    }
+
+    if !in_seq {
+        seq.clone_from_slice(&scratch);
+    }
+    inv_cnt
+}
+
+/// All inversions of `seq` as index pairs `(i, j)`, with `i < j` and
+/// `seq[i] > seq[j]`, in O(n log n + k) for k inversions. Pairs come in no
+/// particular order.
+pub fn inversion_pairs<T: Ord>(seq: &[T]) -> Vec<(usize, usize)> {
+    let mut pairs = vec![];
+    visit_inversions(seq,
+                     &mut |i, smaller_after: &[usize]| {
+                         pairs.extend(smaller_after.iter().map(|&j| (i, j)));
+                     });
+    pairs
+}
+
+/// The inversion vector of `seq`: for each element, the number of later
+/// elements smaller than it.
+pub fn inversion_vector<T: Ord>(seq: &[T]) -> Vec<usize> {
+    let mut counts = vec![0; seq.len()];
+    visit_inversions(seq,
+                     &mut |i, smaller_after: &[usize]| counts[i] += smaller_after.len());
+    counts
+}
+
+// Merge sorts the indices of `seq`, calling `visit(i, js)` with each index i
+// and a batch of later indices js whose items are smaller than seq[i].
+fn visit_inversions<T, F>(seq: &[T], visit: &mut F)
+    where T: Ord,
+          F: FnMut(usize, &[usize])
+{
+    let mut idx: Vec<usize> = (0..seq.len()).collect();
+    let mut scratch = idx.clone();
+    sort_indices(seq, &mut idx[..], &mut scratch[..], visit);
+}
+
+fn sort_indices<T, F>(seq: &[T], idx: &mut [usize], scratch: &mut [usize], visit: &mut F)
+    where T: Ord,
+          F: FnMut(usize, &[usize])
+{
+    let n = idx.len();
+    if n <= 1 {
+        return;
+    }
+    let mid = n / 2;
+    sort_indices(seq, &mut idx[..mid], &mut scratch[..mid], visit);
+    sort_indices(seq, &mut idx[mid..], &mut scratch[mid..], visit);
+
+    let mut i = 0;
+    let mut j = mid;
+    for k in 0..n {
+        if j >= n || (i < mid && seq[idx[i]] <= seq[idx[j]]) {
+            // idx[mid..j] went out before idx[i], they are the smaller later ones
+            if j > mid {
+                visit(idx[i], &idx[mid..j]);
+            }
+            scratch[k] = idx[i];
+            i += 1;
+        } else {
+            scratch[k] = idx[j];
+            j += 1;
+        }
+    }
+    idx.copy_from_slice(&scratch[..n]);
+}
+
+/// Fenwick tree (binary indexed tree) over `n` slots, point updates and
+/// prefix sums in O(log n).
+pub struct FenwickTree {
+    // 1-indexed, tree[i] sums the slots (i - lowbit(i), i]
+    tree: Vec<i64>,
+}
+
+impl FenwickTree {
+    pub fn new(n: usize) -> FenwickTree {
+        FenwickTree { tree: vec![0; n + 1] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds `delta` to slot `i`.
+    pub fn add(&mut self, i: usize, delta: i64) {
+        assert!(i < self.len(), "index out of bounds");
+        let mut k = i + 1;
+        while k < self.tree.len() {
+            self.tree[k] += delta;
+            k += k & k.wrapping_neg();
+        }
+    }
+
+    /// Sum of slots `0..i`.
+    pub fn prefix_sum(&self, i: usize) -> i64 {
+        assert!(i <= self.len(), "index out of bounds");
+        let mut sum = 0;
+        let mut k = i;
+        while k > 0 {
+            sum += self.tree[k];
+            k -= k & k.wrapping_neg();
+        }
+        sum
+    }
+
+    /// Sum of slots `lo..hi`.
+    pub fn range_sum(&self, lo: usize, hi: usize) -> i64 {
+        self.prefix_sum(hi) - self.prefix_sum(lo)
+    }
+}
+
+
+/// Online inversion counting for a stream of keys from a known universe.
+pub struct InversionCounter<T> {
+    // sorted distinct keys, a key's rank is its index
+    keys: Vec<T>,
+    seen: FenwickTree,
+    pushed: usize,
+    inversions: u64,
+}
+
+impl<T: Ord + Clone> InversionCounter<T> {
+    /// Compresses the keys of `universe`, duplicates are fine.
+    pub fn new(universe: &[T]) -> InversionCounter<T> {
+        let mut keys = universe.to_vec();
+        keys.sort();
+        keys.dedup();
+        let n = keys.len();
+        InversionCounter {
+            keys: keys,
+            seen: FenwickTree::new(n),
+            pushed: 0,
+            inversions: 0,
+        }
+    }
+
+    /// Appends `key` to the stream, returns the number of earlier keys
+    /// greater than it. A key outside the universe is not appended, `None`.
+    pub fn push(&mut self, key: &T) -> Option<u64> {
+        let rank = match self.keys.binary_search(key) {
+            Ok(rank) => rank,
+            Err(_) => return None,
+        };
+        let greater = self.pushed as u64 - self.seen.prefix_sum(rank + 1) as u64;
+        self.seen.add(rank, 1);
+        self.pushed += 1;
+        self.inversions += greater;
+        Some(greater)
+    }
+
+    /// Inversions in the stream so far.
+    pub fn inversions(&self) -> u64 {
+        self.inversions
+    }
+
+    pub fn len(&self) -> usize {
+        self.pushed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pushed == 0
+    }
+}
+
+/// Inversion counts of every window `seq[i..i + w]`, in O(n log n).
+pub fn sliding_window_inversions<T: Ord>(seq: &[T], w: usize) -> Vec<u64> {
+    assert!(w > 0, "empty window");
+    if w > seq.len() {
+        return vec![];
+    }
+    let (ranks, n) = compress(seq);
+    let mut window = FenwickTree::new(n);
+    let mut inv_cnt = 0;
+    let mut counts = Vec::with_capacity(seq.len() - w + 1);
+
+    for (i, &r) in ranks.iter().enumerate() {
+        // every item in the window greater than the new one
+        inv_cnt += window.range_sum(r + 1, n) as u64;
+        window.add(r, 1);
+        if i + 1 >= w {
+            if i + 1 > w {
+                // the item leaving is before every other one in the window
+                let first = ranks[i - w];
+                window.add(first, -1);
+                inv_cnt -= window.prefix_sum(first) as u64;
+            }
+            counts.push(inv_cnt);
+        }
+    }
+    counts
+}
+
+// ranks of the items of `seq` among its distinct keys, and the number of keys
+fn compress<T: Ord>(seq: &[T]) -> (Vec<usize>, usize) {
+    let mut keys: Vec<&T> = seq.iter().collect();
+    keys.sort();
+    keys.dedup();
+    let ranks = seq.iter().map(|x| keys.binary_search(&x).unwrap()).collect();
+    (ranks, keys.len())
+}
+
+/// Kendall tau distance between two rankings of the same items, the number
+/// of pairs the two rankings order differently.
+pub fn kendall_tau_distance<L: Ord>(a: &[L], b: &[L]) -> u64 {
+    assert_eq!(a.len(), b.len(), "rankings of different length");
+    let rank_in_b: BTreeMap<&L, usize> = b.iter().enumerate().map(|(i, l)| (l, i)).collect();
+    assert_eq!(rank_in_b.len(), b.len(), "duplicated item in ranking");
+
+    let mut ranks: Vec<usize> = a.iter()
+                                 .map(|l| *rank_in_b.get(l).expect("item missing in ranking"))
+                                 .collect();
+    let distinct: BTreeSet<usize> = ranks.iter().cloned().collect();
+    assert_eq!(distinct.len(), ranks.len(), "duplicated item in ranking");
+    count_inversions(&mut ranks[..])
+}
+
+/// Kendall rank correlation of two rankings of the same items, from 1
+/// (same order) to -1 (reversed). NaN for less than two items.
+pub fn kendall_tau<L: Ord>(a: &[L], b: &[L]) -> f64 {
+    let n = a.len() as f64;
+    let n0 = n * (n - 1.0) / 2.0;
+    1.0 - 2.0 * kendall_tau_distance(a, b) as f64 / n0
+}
+
+/// Kendall tau-b correlation of paired observations `(x[i], y[i])`, which
+/// accounts for ties in either variable, in O(n log n) (Knight's algorithm).
+///
+/// NaN when either variable is constant or there are less than two pairs.
+pub fn kendall_tau_b<X: Ord, Y: Ord>(x: &[X], y: &[Y]) -> f64 {
+    assert_eq!(x.len(), y.len(), "observations of different length");
+    let n = x.len() as u64;
+    let n0 = n * n.saturating_sub(1) / 2;
+
+    let mut pairs: Vec<(&X, &Y)> = x.iter().zip(y.iter()).collect();
+    pairs.sort();
+    let xs: Vec<&X> = pairs.iter().map(|p| p.0).collect();
+    // ties in x
+    let n1 = tied_pairs(&xs);
+    // joint ties in x and y
+    let n3 = tied_pairs(&pairs);
+
+    // pairs sharing x are sorted by y, so each inversion is a discordant pair
+    let mut ys: Vec<&Y> = pairs.iter().map(|p| p.1).collect();
+    let discordant = count_inversions(&mut ys[..]);
+    // ties in y
+    let n2 = tied_pairs(&ys);
+
+    let numerator = n0 as f64 - n1 as f64 - n2 as f64 + n3 as f64 - 2.0 * discordant as f64;
+    numerator / ((n0 - n1) as f64 * (n0 - n2) as f64).sqrt()
+}
+
+// number of equal pairs in a sorted sequence
+fn tied_pairs<T: PartialEq>(sorted: &[T]) -> u64 {
+    let mut cnt = 0;
+    let mut run = 1;
+    for i in 1..sorted.len() + 1 {
+        if i < sorted.len() && sorted[i] == sorted[i - 1] {
+            run += 1;
+        } else {
+            cnt += run * (run - 1) / 2;
+            run = 1;
+        }
+    }
+    cnt
+}
+
 // In merge process, let i is used for indexing left sub-array and j for right sub-array.
 // At any step in merge(), if a[i] is greater than a[j], then there are (mid – i) inversions.
 // because left and right subarrays are sorted, so all the remaining elements
 // in left-subarray (a[i+1], a[i+2] … a[mid]) will be greater than a[j];
 // [*]: http://www.geeksforgeeks.org/counting-inversions/
-fn merge(seq: &mut [usize], mid: usize) -> usize {
+//
+// Merges the sorted runs src[..mid] and src[mid..] into dst, which has the same length.
+fn merge<T, O>(src: &[T], mid: usize, dst: &mut [T], observer: &mut O) -> u64
+    where T: Ord + Clone,
+          O: SortObserver
+{
     let mut inv_cnt = 0;
     let mut i = 0;
     let mut j = mid;
-    let n = seq.len();
+    let mut k = 0;
+    let n = src.len();
 
-    let mut temp = Vec::with_capacity(n);
-
-    while i <= mid - 1 && j < n {
-        if seq[i] <= seq[j] {
-            temp.push(seq[i]);
+    while i < mid && j < n {
+        observer.on_compare();
+        if src[i] <= src[j] {
+            dst[k] = src[i].clone();
             i += 1;
         } else {
-            inv_cnt += mid - i;
-            temp.push(seq[j]);
+            inv_cnt += (mid - i) as u64;
+            dst[k] = src[j].clone();
             j += 1;
         }
+        observer.on_swap();
+        k += 1;
     }
 
-    while i <= mid - 1 {
-        temp.push(seq[i]);
+    while i < mid {
+        dst[k] = src[i].clone();
+        observer.on_swap();
         i += 1;
+        k += 1;
     }
 
     while j < n {
-        temp.push(seq[j]);
+        dst[k] = src[j].clone();
+        observer.on_swap();
         j += 1;
+        k += 1;
     }
 
-    seq.clone_from_slice(&temp);
     inv_cnt
 }
 
@@ -62,4 +441,166 @@ mod tests {
         assert_eq!(merge_sort_and_count_inversions(&mut [1, 6, 3, 2, 4, 5]), 5);
     }
 
+    #[test]
+    fn test_count_inversions_generic() {
+        let mut v = vec!["d", "a", "c", "b"];
+        assert_eq!(count_inversions(&mut v[..]), 4);
+        assert_eq!(&v, &["a", "b", "c", "d"]);
+
+        let v = vec![5_i64, -4, 3, 2, 1];
+        assert_eq!(count_inversions_of(&v), 7);
+        assert_eq!(&v, &[5, -4, 3, 2, 1]);
+
+        let v: Vec<u32> = (0..1000).rev().collect();
+        assert_eq!(count_inversions_of(&v), 1000 * 999 / 2);
+        assert_eq!(count_inversions_of::<u8>(&[]), 0);
+
+        // only Ord is needed, equal items are no inversion
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Label(u32, &'static str);
+        let mut v = vec![Label(3, "c"), Label(1, "a"), Label(3, "c"), Label(2, "b"), Label(0, "")];
+        assert_eq!(count_inversions(&mut v[..]), 7);
+        assert_eq!(v,
+                   vec![Label(0, ""), Label(1, "a"), Label(2, "b"), Label(3, "c"), Label(3, "c")]);
+
+        for n in 0..40 {
+            let v: Vec<u64> = (0..n).map(|i| (i * 7919) % 13).collect();
+            let mut w = v.clone();
+            let mut sorted = v.clone();
+            sorted.sort();
+            assert_eq!(count_inversions(&mut w[..]), count_inversions_of(&v));
+            assert_eq!(w, sorted);
+        }
+    }
+
+    #[test]
+    fn test_count_inversions_bottom_up() {
+        for n in 0..40 {
+            let v: Vec<u64> = (0..n).map(|i| (i * 7919) % 13).collect();
+            let mut w = v.clone();
+            let mut x = v.clone();
+            assert_eq!(count_inversions_bottom_up(&mut w[..]), count_inversions(&mut x[..]));
+            assert_eq!(w, x);
+        }
+        assert_eq!(count_inversions_bottom_up(&mut [1, 6, 3, 2, 4, 5]), 5);
+    }
+
+    #[test]
+    fn test_inversion_pairs() {
+        let mut pairs = inversion_pairs(&[1, 3, 5, 2, 4, 6]);
+        pairs.sort();
+        assert_eq!(pairs, vec![(1, 3), (2, 3), (2, 4)]);
+
+        let v = vec!["e", "b", "d", "a", "c", "b"];
+        let mut pairs = inversion_pairs(&v);
+        pairs.sort();
+        let mut expected = vec![];
+        for i in 0..v.len() {
+            for j in i + 1..v.len() {
+                if v[i] > v[j] {
+                    expected.push((i, j));
+                }
+            }
+        }
+        assert_eq!(pairs, expected);
+        assert_eq!(pairs.len() as u64, count_inversions_of(&v));
+
+        assert!(inversion_pairs(&[1, 1, 2]).is_empty());
+    }
+
+    #[test]
+    fn test_inversion_vector() {
+        assert_eq!(inversion_vector(&[1, 6, 3, 2, 4, 5]), vec![0, 4, 1, 0, 0, 0]);
+        assert_eq!(inversion_vector(&[5, 4, 3, 2, 1]), vec![4, 3, 2, 1, 0]);
+        assert_eq!(inversion_vector::<u8>(&[]), vec![]);
+    }
+
+    #[test]
+    fn test_fenwick_tree() {
+        let mut ft = FenwickTree::new(10);
+        for i in 0..10 {
+            ft.add(i, i as i64);
+        }
+        assert_eq!(ft.len(), 10);
+        assert_eq!(ft.prefix_sum(0), 0);
+        assert_eq!(ft.prefix_sum(10), 45);
+        assert_eq!(ft.range_sum(3, 7), 3 + 4 + 5 + 6);
+        ft.add(5, -5);
+        assert_eq!(ft.range_sum(3, 7), 3 + 4 + 6);
+    }
+
+    #[test]
+    fn test_inversion_counter() {
+        let stream = vec!["b", "d", "a", "c", "a"];
+        let mut counter = InversionCounter::new(&stream);
+        let new_inversions: Vec<u64> = stream.iter().map(|k| counter.push(k).unwrap()).collect();
+        assert_eq!(new_inversions, vec![0, 0, 2, 1, 3]);
+        assert_eq!(counter.inversions(), count_inversions_of(&stream));
+        assert_eq!(counter.len(), 5);
+
+        assert_eq!(counter.push(&"e"), None);
+        assert_eq!(counter.inversions(), 6);
+        assert_eq!(counter.len(), 5);
+    }
+
+    #[test]
+    fn test_sliding_window_inversions() {
+        let v = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3];
+        for w in 1..v.len() + 2 {
+            let expected: Vec<u64> = v.windows(w).map(|win| count_inversions_of(win)).collect();
+            assert_eq!(sliding_window_inversions(&v, w), expected);
+        }
+    }
+
+    #[test]
+    fn test_kendall_tau() {
+        let a = ["A", "B", "C", "D", "E"];
+        let b = ["C", "D", "A", "B", "E"];
+        assert_eq!(kendall_tau_distance(&a, &b), 4);
+        assert_eq!(kendall_tau_distance(&a, &a), 0);
+        assert_eq!(kendall_tau(&a, &a), 1.0);
+        assert!((kendall_tau(&a, &b) - 0.2).abs() < 1e-12);
+
+        let r = ["E", "D", "C", "B", "A"];
+        assert_eq!(kendall_tau_distance(&a, &r), 10);
+        assert_eq!(kendall_tau(&a, &r), -1.0);
+    }
+
+    #[test]
+    fn test_kendall_tau_b() {
+        // no ties, same as tau
+        assert!((kendall_tau_b(&[1, 2, 3, 4, 5], &[3, 4, 1, 2, 5]) - 0.2).abs() < 1e-12);
+
+        // scipy.stats.kendalltau([12, 2, 1, 12, 2], [1, 4, 7, 1, 0])
+        let tau = kendall_tau_b(&[12, 2, 1, 12, 2], &[1, 4, 7, 1, 0]);
+        assert!((tau - (-0.47140452079103173)).abs() < 1e-12);
+
+        assert!(kendall_tau_b(&[1, 1, 1], &[1, 2, 3]).is_nan());
+    }
+
+    #[test]
+    fn test_kendall_tau_distance_not_permutation() {
+        use std::panic;
+
+        assert!(panic::catch_unwind(|| kendall_tau_distance(&[1, 2, 3], &[1, 2, 4])).is_err());
+        // a duplicate in the first ranking hides the missing 3
+        assert!(panic::catch_unwind(|| kendall_tau_distance(&[1, 1, 2], &[1, 2, 3])).is_err());
+        assert!(panic::catch_unwind(|| kendall_tau_distance(&[1, 2, 3], &[1, 1, 2])).is_err());
+    }
+
+    #[test]
+    fn test_merge_sort_observed() {
+        use super::super::instrument::SortStats;
+
+        let mut v = [5, 4, 3, 2, 1, 0, 7, 6];
+        let mut stats = SortStats::new();
+        assert_eq!(merge_sort_observed(&mut v, &mut stats), 16);
+        assert_eq!(v, [0, 1, 2, 3, 4, 5, 6, 7]);
+        // every level moves all 8 items
+        assert_eq!(stats.swaps, 24);
+        assert_eq!(stats.max_depth, 3);
+        assert_eq!(stats.partition_sizes.values().sum::<usize>(), 7);
+        assert!(stats.comparisons <= 17);
+    }
+
 }
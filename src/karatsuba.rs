@@ -0,0 +1,275 @@
+//! Karatsuba multiplication of arbitrary-precision unsigned integers.
+
+use std::cmp;
+use std::error;
+use std::fmt;
+use std::ops::Mul;
+use std::str::FromStr;
+
+/// Each digit holds 9 decimal digits.
+const BASE: u64 = 1_000_000_000;
+const BASE_DIGITS: usize = 9;
+
+/// Operands with fewer digits than this are multiplied by the grade-school
+/// algorithm.
+pub const KARATSUBA_THRESHOLD: usize = 32;
+
+
+/// An arbitrary-precision unsigned integer.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BigUint {
+    // base 10^9, least significant first, no leading zero digits
+    digits: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> BigUint {
+        BigUint { digits: vec![] }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    fn from_digits(mut digits: Vec<u32>) -> BigUint {
+        trim(&mut digits);
+        BigUint { digits: digits }
+    }
+
+    /// Karatsuba multiplication, grade-school below `KARATSUBA_THRESHOLD` digits.
+    pub fn karatsuba_mul(&self, other: &BigUint) -> BigUint {
+        BigUint::from_digits(karatsuba(&self.digits, &other.digits, KARATSUBA_THRESHOLD))
+    }
+
+    /// Grade-school multiplication in O(n * m).
+    pub fn schoolbook_mul(&self, other: &BigUint) -> BigUint {
+        BigUint::from_digits(schoolbook(&self.digits, &other.digits))
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(mut n: u64) -> BigUint {
+        let mut digits = vec![];
+        while n > 0 {
+            digits.push((n % BASE) as u32);
+            n /= BASE;
+        }
+        BigUint { digits: digits }
+    }
+}
+
+impl<'a, 'b> Mul<&'b BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        self.karatsuba_mul(other)
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: BigUint) -> BigUint {
+        self.karatsuba_mul(&other)
+    }
+}
+
+/// Error of parsing a `BigUint` from a string that is not a decimal number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigUintError;
+
+impl fmt::Display for ParseBigUintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid decimal digit string")
+    }
+}
+
+impl error::Error for ParseBigUintError {
+    fn description(&self) -> &str {
+        "invalid decimal digit string"
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigUintError;
+
+    fn from_str(s: &str) -> Result<BigUint, ParseBigUintError> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigUintError);
+        }
+        // chunks of 9 decimal digits, from the least significant end
+        let mut digits = vec![];
+        let mut end = s.len();
+        while end > 0 {
+            let start = end.saturating_sub(BASE_DIGITS);
+            digits.push(s[start..end].parse().unwrap());
+            end = start;
+        }
+        Ok(BigUint::from_digits(digits))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.digits.split_last() {
+            None => write!(f, "0"),
+            Some((most, rest)) => {
+                try!(write!(f, "{}", most));
+                for d in rest.iter().rev() {
+                    try!(write!(f, "{:09}", d));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+
+// drop leading (most significant) zero digits
+fn trim(digits: &mut Vec<u32>) {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+}
+
+// a * b, a.len() + b.len() digits, maybe with leading zeros
+fn schoolbook(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut res = vec![0; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &y) in b.iter().enumerate() {
+            let t = res[i + j] as u64 + x as u64 * y as u64 + carry;
+            res[i + j] = (t % BASE) as u32;
+            carry = t / BASE;
+        }
+        res[i + b.len()] = carry as u32;
+    }
+    res
+}
+
+// a * b, a.len() + b.len() digits, maybe with leading zeros
+fn karatsuba(a: &[u32], b: &[u32], threshold: usize) -> Vec<u32> {
+    // the sums below must be shorter than the operands, so never split
+    // anything shorter than 4 digits
+    if cmp::min(a.len(), b.len()) < cmp::max(threshold, 4) {
+        return schoolbook(a, b);
+    }
+
+    // a = a1 * B^m + a0, b = b1 * B^m + b0
+    let m = cmp::max(a.len(), b.len()) / 2;
+    let (a0, a1) = a.split_at(cmp::min(m, a.len()));
+    let (b0, b1) = b.split_at(cmp::min(m, b.len()));
+
+    let mut z0 = karatsuba(a0, b0, threshold);
+    let mut z2 = karatsuba(a1, b1, threshold);
+    // z1 = (a0 + a1)(b0 + b1) - z0 - z2 = a1 * b0 + a0 * b1
+    let mut z1 = karatsuba(&add(a0, a1), &add(b0, b1), threshold);
+    trim(&mut z0);
+    trim(&mut z2);
+    sub_assign(&mut z1, &z0);
+    sub_assign(&mut z1, &z2);
+    trim(&mut z1);
+
+    let mut res = vec![0; a.len() + b.len()];
+    add_assign_at(&mut res, &z0, 0);
+    add_assign_at(&mut res, &z1, m);
+    add_assign_at(&mut res, &z2, 2 * m);
+    res
+}
+
+fn add(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut res = a.to_vec();
+    res.resize(cmp::max(a.len(), b.len()) + 1, 0);
+    add_assign_at(&mut res, b, 0);
+    trim(&mut res);
+    res
+}
+
+// res += x * B^shift, the sum must fit in res
+fn add_assign_at(res: &mut [u32], x: &[u32], shift: usize) {
+    let mut carry = 0;
+    let mut i = 0;
+    while i < x.len() || carry > 0 {
+        let t = res[shift + i] as u64 + x.get(i).map_or(0, |&d| d as u64) + carry;
+        res[shift + i] = (t % BASE) as u32;
+        carry = t / BASE;
+        i += 1;
+    }
+}
+
+// a -= b, requires a >= b
+fn sub_assign(a: &mut [u32], b: &[u32]) {
+    let mut borrow = 0;
+    let mut i = 0;
+    while i < b.len() || borrow > 0 {
+        let d = b.get(i).map_or(0, |&d| d as i64) + borrow;
+        let mut t = a[i] as i64 - d;
+        if t < 0 {
+            t += BASE as i64;
+            borrow = 1;
+        } else {
+            borrow = 0;
+        }
+        a[i] = t as u32;
+        i += 1;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use self::super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        for s in &["0", "7", "1000000000", "123456789012345678901234567890"] {
+            assert_eq!(s.parse::<BigUint>().unwrap().to_string(), *s);
+        }
+        assert_eq!("000042".parse::<BigUint>().unwrap().to_string(), "42");
+        assert_eq!("".parse::<BigUint>(), Err(ParseBigUintError));
+        assert_eq!("12a".parse::<BigUint>(), Err(ParseBigUintError));
+        assert_eq!("-1".parse::<BigUint>(), Err(ParseBigUintError));
+        assert_eq!(BigUint::from(1234567890123_u64).to_string(), "1234567890123");
+        assert!(BigUint::from(0).is_zero());
+    }
+
+    #[test]
+    fn test_karatsuba_course_assignment() {
+        let x: BigUint = "3141592653589793238462643383279502884197169399375105820974944592"
+                             .parse()
+                             .unwrap();
+        let y: BigUint = "2718281828459045235360287471352662497757247093699959574966967627"
+                             .parse()
+                             .unwrap();
+        let expected = "8539734222673567065463550869546574495034888535765114961879601127067743044\
+                        893204848617875072216249073013374895871952806582723184";
+        assert_eq!((&x * &y).to_string(), expected);
+        // 8 limbs are below KARATSUBA_THRESHOLD, force one or two levels of
+        // recursion, it never splits operands shorter than 4 limbs
+        for &threshold in &[3, 4, 8] {
+            let z = BigUint::from_digits(karatsuba(&x.digits, &y.digits, threshold));
+            assert_eq!(z.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_karatsuba_against_schoolbook() {
+        let mut s = String::new();
+        for i in 0..1500 {
+            s.push_str(&((i * 7919 + 13) % 10).to_string());
+        }
+        let x: BigUint = s.parse().unwrap();
+        let y: BigUint = s[..700].parse().unwrap();
+        let z: BigUint = "999999999999999999999999999999999999999999999999".parse().unwrap();
+
+        for &(a, b) in &[(&x, &y), (&y, &x), (&x, &x), (&z, &z), (&x, &z)] {
+            let expected = a.schoolbook_mul(b);
+            assert_eq!(a.karatsuba_mul(b), expected);
+            for &threshold in &[3, 5, 13, 40] {
+                assert_eq!(BigUint::from_digits(karatsuba(&a.digits, &b.digits, threshold)),
+                           expected);
+            }
+        }
+        assert!((&x * &BigUint::zero()).is_zero());
+    }
+}
@@ -1,32 +1,202 @@
 extern crate rand;
 
+use std::cmp;
+use std::error;
+use std::fmt;
 use std::collections::btree_map::BTreeMap;
+use std::collections::btree_set::BTreeSet;
 use std::iter::FromIterator;
+use std::thread;
 
-use self::rand::Rng;
+use self::rand::{Rng, SeedableRng, StdRng};
 
+use super::clustering::UnionFind;
+use super::mst::{Edge, EdgeWeightedGraph};
 
+
+/// An undirected graph with positive integer edge weights. Parallel edges are
+/// kept as one edge of their summed weight, so unit weights make a multigraph.
 #[derive(Debug, Clone)]
 pub struct Graph {
-    pub adj: BTreeMap<u32, Vec<u32>>,
+    /// neighbor => weight of the edge to it, for each vertex
+    pub adj: BTreeMap<u32, BTreeMap<u32, u64>>,
+    /// original vertices merged into each super-vertex, by `contract`
+    merged: BTreeMap<u32, Vec<u32>>,
+}
+
+
+/// A cut of the graph, as found by the contraction algorithm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCut {
+    /// Original vertices on each side of the cut, sorted.
+    pub sides: (Vec<u32>, Vec<u32>),
+    /// Original edges crossing the cut, as `(u, v, weight)` with `u` in the
+    /// first side.
+    pub crossing_edges: Vec<(u32, u32, u64)>,
+}
+
+impl MinCut {
+    /// Total weight of the edges crossing the cut, their number for unit weights.
+    pub fn size(&self) -> u64 {
+        self.crossing_edges.iter().map(|e| e.2).sum()
+    }
+}
+
+/// An inconsistency in the adjacency lists of a `Graph`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphError {
+    /// Not one adjacency list per vertex.
+    LengthMismatch { vertices: usize, lists: usize },
+    /// The vertex is given more than once.
+    DuplicateVertex(u32),
+    /// `vertex` lists `neighbor`, which is not a vertex of the graph.
+    UnknownNeighbor { vertex: u32, neighbor: u32 },
+    /// The vertex lists itself.
+    SelfLoop(u32),
+    /// `u` lists `v` with weight `forward`, but `v` lists `u` with `backward`.
+    Asymmetric { u: u32, v: u32, forward: u64, backward: u64 },
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GraphError::LengthMismatch { vertices, lists } => {
+                write!(f, "{} vertices but {} adjacency lists", vertices, lists)
+            }
+            GraphError::DuplicateVertex(u) => write!(f, "vertex {} given twice", u),
+            GraphError::UnknownNeighbor { vertex, neighbor } => {
+                write!(f, "vertex {} lists unknown neighbor {}", vertex, neighbor)
+            }
+            GraphError::SelfLoop(u) => write!(f, "self loop on vertex {}", u),
+            GraphError::Asymmetric { u, v, forward, backward } => {
+                write!(f,
+                       "edge {}-{} has weight {} but {}-{} has weight {}",
+                       u,
+                       v,
+                       forward,
+                       v,
+                       u,
+                       backward)
+            }
+        }
+    }
+}
+
+impl error::Error for GraphError {
+    fn description(&self) -> &str {
+        match *self {
+            GraphError::LengthMismatch { .. } => "vertex and adjacency list counts differ",
+            GraphError::DuplicateVertex(_) => "duplicate vertex",
+            GraphError::UnknownNeighbor { .. } => "unknown neighbor",
+            GraphError::SelfLoop(_) => "self loop",
+            GraphError::Asymmetric { .. } => "asymmetric adjacency",
+        }
+    }
 }
 
 impl Graph {
+    /// A unit weight graph from the adjacency lists `es` of vertices `vs`,
+    /// where every edge is listed at both ends and may be repeated.
     pub fn new(vs: Vec<u32>, es: Vec<Vec<u32>>) -> Graph {
-        let adj = vs.iter()
-                    .enumerate()
-                    .map(|(i, &u)| (u, es[i].clone()))
-                    .collect();
-        Graph { adj: adj }
+        assert_eq!(vs.len(), es.len(), "one adjacency list per vertex");
+        vs.into_iter().zip(es.into_iter()).collect()
+    }
+
+    /// Like `new`, but fails on the first inconsistency in the adjacency lists.
+    pub fn try_new(vs: Vec<u32>, es: Vec<Vec<u32>>) -> Result<Graph, GraphError> {
+        if vs.len() != es.len() {
+            return Err(GraphError::LengthMismatch {
+                vertices: vs.len(),
+                lists: es.len(),
+            });
+        }
+        let mut seen = BTreeSet::new();
+        for &u in &vs {
+            if !seen.insert(u) {
+                return Err(GraphError::DuplicateVertex(u));
+            }
+        }
+        let g = Graph::new(vs, es);
+        match g.validate().into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(g),
+        }
+    }
+
+    /// Every inconsistency in the adjacency, empty if it is a valid
+    /// undirected graph. An asymmetric edge is reported once.
+    pub fn validate(&self) -> Vec<GraphError> {
+        let mut errors = vec![];
+        for (&u, vs) in &self.adj {
+            for (&v, &forward) in vs {
+                if u == v {
+                    errors.push(GraphError::SelfLoop(u));
+                } else if !self.adj.contains_key(&v) {
+                    errors.push(GraphError::UnknownNeighbor {
+                        vertex: u,
+                        neighbor: v,
+                    });
+                } else {
+                    let backward = self.weight(v, u);
+                    // report from the smaller end, unless it does not list the edge
+                    if forward != backward && (u < v || backward == 0) {
+                        errors.push(GraphError::Asymmetric {
+                            u: u,
+                            v: v,
+                            forward: forward,
+                            backward: backward,
+                        });
+                    }
+                }
+            }
+        }
+        errors
+    }
+
+    /// A graph of the weighted edges `(u, v, weight)`.
+    pub fn from_weighted_edges<I>(edges: I) -> Graph
+        where I: IntoIterator<Item = (u32, u32, u64)>
+    {
+        let mut g = Graph {
+            adj: BTreeMap::new(),
+            merged: BTreeMap::new(),
+        };
+        for (u, v, weight) in edges {
+            g.add_edge(u, v, weight);
+        }
+        g
+    }
+
+    /// Adds an undirected edge, summed into any edge already between u and v.
+    pub fn add_edge(&mut self, u: u32, v: u32, weight: u64) {
+        assert!(weight > 0, "edge weight must be positive");
+        *self.adj.entry(u).or_insert_with(BTreeMap::new).entry(v).or_insert(0) += weight;
+        *self.adj.entry(v).or_insert_with(BTreeMap::new).entry(u).or_insert(0) += weight;
+    }
+
+    pub fn weight(&self, u: u32, v: u32) -> u64 {
+        self.adj.get(&u).and_then(|vs| vs.get(&v)).cloned().unwrap_or(0)
     }
 
     pub fn random_edge(&self) -> (u32, u32) {
-        let mut rng = rand::thread_rng();
+        self.random_edge_with(&mut rand::thread_rng())
+    }
 
-        let end_vertices: Vec<_> = self.adj.keys().collect();
-        let u = rng.choose(&end_vertices).unwrap();
-        let v = rng.choose(self.adj.get(u).unwrap()).unwrap();
-        (**u, *v)
+    /// A random edge, with probability proportional to its weight.
+    pub fn random_edge_with<R: Rng>(&self, rng: &mut R) -> (u32, u32) {
+        // every edge is counted at both ends, pick one of the 2W endpoint slots
+        let total: u64 = self.adj.values().flat_map(|vs| vs.values()).sum();
+        assert!(total > 0, "no edge in graph");
+        let mut r = rng.gen_range(0, total);
+        for (&u, vs) in &self.adj {
+            for (&v, &w) in vs {
+                if r < w {
+                    return (u, v);
+                }
+                r -= w;
+            }
+        }
+        unreachable!()
     }
 
     pub fn contract(&mut self, u: u32, v: u32) {
@@ -34,59 +204,469 @@ impl Graph {
         assert!(self.adj.contains_key(&u) && self.adj.contains_key(&v));
 
         let v_to = self.adj.remove(&v).expect("v not in Graph");
-
-        self.adj
-            .get_mut(&u)
-            .map(|u_to| {
-                *u_to = u_to.iter()
-                            .cloned()
-                            .chain(v_to.into_iter())
-                            .filter(|&w| w != u && w != v)
-                            .collect();
-            });
-        self.adj
-            .values_mut()
-            .map(|vs| {
-                vs.iter_mut()
-                  .map(|w| {
-                      if *w == v {
-                          *w = u;
-                      }
-                  })
-                  .last()
-            })
-            .last();
+        let mut absorbed = self.merged.remove(&v).unwrap_or_else(|| vec![v]);
+        self.merged.entry(u).or_insert_with(|| vec![u]).append(&mut absorbed);
+
+        // edges between u and v become self loops, drop them
+        self.adj.get_mut(&u).map(|u_to| u_to.remove(&v));
+        for (w, weight) in v_to {
+            if w == u || w == v {
+                continue;
+            }
+            // parallel edges sum their weights
+            *self.adj.get_mut(&u).unwrap().entry(w).or_insert(0) += weight;
+            let w_to = self.adj.get_mut(&w).unwrap();
+            w_to.remove(&v);
+            *w_to.entry(u).or_insert(0) += weight;
+        }
     }
 
     pub fn vertices(&self) -> usize {
         self.adj.len()
     }
 
+    /// Number of edges, counting each with its weight as multiplicity.
     pub fn edges(&self) -> usize {
         // assume undirected graph
-        self.adj.values().map(|vs| vs.len()).sum::<usize>() / 2
+        self.adj.values().flat_map(|vs| vs.values()).sum::<u64>() as usize / 2
+    }
+
+    /// Contracts random edges until `t` vertices are left.
+    pub fn contract_to(&mut self, t: usize) {
+        self.contract_to_with(t, &mut rand::thread_rng())
+    }
+
+    pub fn contract_to_with<R: Rng>(&mut self, t: usize, rng: &mut R) {
+        while self.vertices() > t {
+            let (u, v) = self.random_edge_with(rng);
+            self.contract(u, v);
+        }
     }
 
-    pub fn minimum_cut_karger(&self) -> Graph {
+    /// Original vertices merged into `u` by contractions so far.
+    pub fn members(&self, u: u32) -> Vec<u32> {
+        self.merged.get(&u).cloned().unwrap_or_else(|| vec![u])
+    }
+
+    pub fn minimum_cut_karger(&self) -> MinCut {
+        self.minimum_cut_karger_with(&mut rand::thread_rng())
+    }
+
+    pub fn minimum_cut_karger_with<R: Rng>(&self, rng: &mut R) -> MinCut {
+        let g = self.contract_karger(rng);
+        self.cut_of(&g)
+    }
+
+    /// Karger-Stein recursive contraction, contracts to about n/√2 vertices
+    /// twice independently and recurses on both, keeping the smaller cut.
+    ///
+    /// Finds a minimum cut with probability Ω(1 / log n). Every contraction
+    /// scans the adjacency for a random edge, so this takes O(n^3) rather than
+    /// the O(n^2 log n) of the adjacency matrix version.
+    pub fn minimum_cut_karger_stein(&self) -> MinCut {
+        self.minimum_cut_karger_stein_with(&mut rand::thread_rng())
+    }
+
+    pub fn minimum_cut_karger_stein_with<R: Rng>(&self, rng: &mut R) -> MinCut {
+        let g = self.contract_karger_stein(rng);
+        self.cut_of(&g)
+    }
+
+    /// Repeats Karger's algorithm `required_trials(n, failure_probability)`
+    /// times with an RNG seeded by `seed`, so runs are reproducible.
+    pub fn minimum_cut_trials(&self, seed: usize, failure_probability: f64) -> MinCutTrials {
+        run_trials(required_trials(self.vertices(), failure_probability),
+                   &mut StdRng::from_seed(&[seed][..]),
+                   |rng| self.minimum_cut_karger_with(rng))
+    }
+
+    /// The edge list form of the graph, for fast contraction trials.
+    pub fn to_edge_list(&self) -> EdgeListGraph {
+        let labels: Vec<u32> = self.adj.keys().cloned().collect();
+        let index: BTreeMap<u32, usize> = labels.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+
+        let mut edges = vec![];
+        for (&u, vs) in &self.adj {
+            for (&v, &weight) in vs {
+                // every undirected edge is listed at both ends
+                if u < v {
+                    edges.push((index[&u], index[&v], weight));
+                }
+            }
+        }
+        EdgeListGraph {
+            labels: labels,
+            edges: edges,
+        }
+    }
+
+    fn contract_karger<R: Rng>(&self, rng: &mut R) -> Graph {
         if self.vertices() < 2 {
             panic!("single vertex!");
         }
         let mut g = self.clone();
+        g.contract_to_with(2, rng);
+        g
+    }
 
-        while g.vertices() > 2 {
-            let (u, v) = g.random_edge();
-            g.contract(u, v);
+    fn contract_karger_stein<R: Rng>(&self, rng: &mut R) -> Graph {
+        if self.vertices() < 2 {
+            panic!("single vertex!");
+        }
+        let n = self.vertices();
+        if n <= 6 {
+            return self.contract_karger(rng);
         }
 
-        g
+        let t = (1.0 + n as f64 / 2f64.sqrt()).ceil() as usize;
+        let mut best: Option<Graph> = None;
+        for _ in 0..2 {
+            let mut g = self.clone();
+            g.contract_to_with(t, rng);
+            let cut = g.contract_karger_stein(rng);
+            if best.as_ref().map_or(true, |b| cut.edges() < b.edges()) {
+                best = Some(cut);
+            }
+        }
+        best.unwrap()
+    }
+
+    /// The same graph as an `EdgeWeightedGraph`, and the label of each of its
+    /// vertices.
+    pub fn to_edge_weighted(&self) -> (EdgeWeightedGraph, Vec<u32>) {
+        let labels: Vec<u32> = self.adj.keys().cloned().collect();
+        let index: BTreeMap<u32, usize> = labels.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+
+        let mut g = EdgeWeightedGraph::new(labels.len());
+        for (&u, vs) in &self.adj {
+            for (&v, &weight) in vs {
+                // every undirected edge is listed at both ends
+                if u < v {
+                    g.add_edge(Edge::new(index[&u], index[&v], weight as i64));
+                }
+            }
+        }
+        (g, labels)
+    }
+
+    // the cut of this graph given by a contraction of it down to two vertices
+    fn cut_of(&self, contracted: &Graph) -> MinCut {
+        assert_eq!(contracted.vertices(), 2, "not contracted to two vertices");
+        let mut super_vertices = contracted.adj.keys();
+        let mut s = contracted.members(*super_vertices.next().unwrap());
+        let mut t = contracted.members(*super_vertices.next().unwrap());
+        s.sort();
+        t.sort();
+
+        let t_set: BTreeSet<u32> = t.iter().cloned().collect();
+        let mut crossing_edges = vec![];
+        for &u in &s {
+            for (&v, &weight) in self.adj.get(&u).into_iter().flat_map(|vs| vs.iter()) {
+                if t_set.contains(&v) {
+                    crossing_edges.push((u, v, weight));
+                }
+            }
+        }
+
+        MinCut {
+            sides: (s, t),
+            crossing_edges: crossing_edges,
+        }
+    }
+}
+
+
+/// Outcome of repeated contraction trials.
+#[derive(Debug, Clone)]
+pub struct MinCutTrials {
+    /// The smallest cut found.
+    pub best: MinCut,
+    /// Number of trials run.
+    pub trials: usize,
+    /// Number of trials that found a cut of the best size.
+    pub found: usize,
+}
+
+/// Number of independent Karger trials so that all of them miss a given
+/// minimum cut with probability at most `failure_probability`.
+///
+/// One trial succeeds with probability at least 1 / C(n, 2), so
+/// C(n, 2) ln(1 / p) trials fail with probability at most p. With p = 1/n
+/// this is the (n^2 ln n) / 2 bound.
+pub fn required_trials(n: usize, failure_probability: f64) -> usize {
+    assert!(failure_probability > 0.0 && failure_probability < 1.0,
+            "failure probability out of (0, 1)");
+    let pairs = (n * n.saturating_sub(1) / 2) as f64;
+    let trials = (pairs * (1.0 / failure_probability).ln()).ceil() as usize;
+    cmp::max(trials, 1)
+}
+
+
+// Runs `trial` `trials` times with the given RNG, keeping the smallest cut.
+fn run_trials<F>(trials: usize, rng: &mut StdRng, mut trial: F) -> MinCutTrials
+    where F: FnMut(&mut StdRng) -> MinCut
+{
+    assert!(trials > 0, "no trial to run");
+    let mut best = trial(rng);
+    let mut found = 1;
+    for _ in 1..trials {
+        let cut = trial(rng);
+        if cut.size() < best.size() {
+            best = cut;
+            found = 1;
+        } else if cut.size() == best.size() {
+            found += 1;
+        }
+    }
+
+    MinCutTrials {
+        best: best,
+        trials: trials,
+        found: found,
+    }
+}
+
+
+/// Edge list form of a `Graph`. A contraction trial shuffles the edges once
+/// and merges their endpoints in that order with a union-find, skipping
+/// edges inside a merged vertex. That is the same random contraction process
+/// as `Graph::contract_to`, in O(m log m) instead of O(n m) per trial.
+#[derive(Debug, Clone)]
+pub struct EdgeListGraph {
+    labels: Vec<u32>,
+    // endpoints as indices into labels, and weight
+    edges: Vec<(usize, usize, u64)>,
+}
+
+impl EdgeListGraph {
+    pub fn vertices(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Number of edges, counting each with its weight as multiplicity.
+    pub fn edges(&self) -> usize {
+        self.edges.iter().map(|e| e.2).sum::<u64>() as usize
+    }
+
+    /// One contraction trial.
+    pub fn minimum_cut_karger_with<R: Rng>(&self, rng: &mut R) -> MinCut {
+        let n = self.vertices();
+        if n < 2 {
+            panic!("single vertex!");
+        }
+
+        // a weighted shuffle: sorting by exponential keys with rate `weight`,
+        // the next edge is always picked with probability proportional to its
+        // weight among the remaining ones
+        let mut keyed: Vec<(f64, usize)> = self.edges
+                                               .iter()
+                                               .enumerate()
+                                               .map(|(i, e)| {
+                                                   let u: f64 = 1.0 - rng.gen::<f64>();
+                                                   (-u.ln() / e.2 as f64, i)
+                                               })
+                                               .collect();
+        keyed.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let edges: Vec<(usize, usize, u64)> = keyed.iter().map(|&(_, i)| self.edges[i]).collect();
+
+        let mut uf = UnionFind::new(n);
+        for &(u, v, _) in &edges {
+            if uf.count() <= 2 {
+                break;
+            }
+            uf.union(u, v);
+        }
+        // a disconnected graph, merge whole components
+        for v in 1..n {
+            if uf.count() <= 2 {
+                break;
+            }
+            if !uf.connected(0, v) {
+                uf.union(0, v);
+            }
+        }
+
+        let root = uf.find(0);
+        let in_first: Vec<bool> = (0..n).map(|v| uf.find(v) == root).collect();
+        let s = (0..n).filter(|&v| in_first[v]).map(|v| self.labels[v]).collect();
+        let t = (0..n).filter(|&v| !in_first[v]).map(|v| self.labels[v]).collect();
+        let crossing_edges = self.edges
+                                 .iter()
+                                 .filter(|&&(u, v, _)| in_first[u] != in_first[v])
+                                 .map(|&(u, v, weight)| {
+                                     if in_first[u] {
+                                         (self.labels[u], self.labels[v], weight)
+                                     } else {
+                                         (self.labels[v], self.labels[u], weight)
+                                     }
+                                 })
+                                 .collect();
+
+        MinCut {
+            sides: (s, t),
+            crossing_edges: crossing_edges,
+        }
+    }
+
+    /// Same as `Graph::minimum_cut_trials`, on the edge list.
+    pub fn minimum_cut_trials(&self, seed: usize, failure_probability: f64) -> MinCutTrials {
+        run_trials(required_trials(self.vertices(), failure_probability),
+                   &mut StdRng::from_seed(&[seed][..]),
+                   |rng| self.minimum_cut_karger_with(rng))
+    }
+
+    /// `minimum_cut_trials` with the trials split evenly over `threads`
+    /// worker threads. Worker `i` seeds its own RNG with `[seed, i]`, so the
+    /// result only depends on the seed and the thread count.
+    pub fn par_minimum_cut_trials(&self,
+                                  seed: usize,
+                                  failure_probability: f64,
+                                  threads: usize)
+                                  -> MinCutTrials {
+        assert!(threads > 0, "no worker thread");
+        let trials = required_trials(self.vertices(), failure_probability);
+
+        let results: Vec<MinCutTrials> = thread::scope(|s| {
+            let handles: Vec<_> = (0..threads)
+                                      .map(|i| trials / threads + (i < trials % threads) as usize)
+                                      .enumerate()
+                                      .filter(|&(_, n)| n > 0)
+                                      .map(|(i, n)| {
+                                          s.spawn(move || {
+                                              let mut rng = StdRng::from_seed(&[seed, i][..]);
+                                              run_trials(n, &mut rng, |rng| {
+                                                  self.minimum_cut_karger_with(rng)
+                                              })
+                                          })
+                                      })
+                                      .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        // ties go to the lowest numbered worker
+        let best_size = results.iter().map(|r| r.best.size()).min().unwrap();
+        let found = results.iter()
+                           .filter(|r| r.best.size() == best_size)
+                           .map(|r| r.found)
+                           .sum();
+        let best = results.into_iter().find(|r| r.best.size() == best_size).unwrap().best;
+
+        MinCutTrials {
+            best: best,
+            trials: trials,
+            found: found,
+        }
     }
 }
 
 
+/// A minimum cut of an edge-weighted graph.
+#[derive(Debug, Clone)]
+pub struct WeightedMinCut {
+    /// Total weight of the crossing edges.
+    pub weight: i64,
+    /// Vertices on each side of the cut, sorted.
+    pub sides: (Vec<usize>, Vec<usize>),
+    /// Edges crossing the cut.
+    pub crossing_edges: Vec<Edge>,
+}
+
+/// Deterministic global minimum cut by the Stoer-Wagner algorithm, in
+/// O(V^3). Weights should be non-negative, self loops are ignored.
+pub fn stoer_wagner_min_cut(g: &EdgeWeightedGraph) -> WeightedMinCut {
+    let n = g.v();
+    if n < 2 {
+        panic!("single vertex!");
+    }
+
+    // w[u][v] sums the weights of all edges between u and v
+    let mut w = vec![vec![0; n]; n];
+    for e in g.edges() {
+        let u = e.either();
+        let v = e.other(u);
+        if u != v {
+            w[u][v] += e.weight();
+            w[v][u] += e.weight();
+        }
+    }
+
+    // original vertices merged into each vertex
+    let mut groups: Vec<Vec<usize>> = (0..n).map(|v| vec![v]).collect();
+    let mut active: Vec<usize> = (0..n).collect();
+    let mut best: Option<(i64, Vec<usize>)> = None;
+
+    while active.len() > 1 {
+        // maximum adjacency order: repeatedly add the vertex most
+        // tightly connected to the ones added so far
+        let mut added = vec![false; n];
+        let mut key = vec![0; n];
+        let mut prev = active[0];
+        let mut last = active[0];
+        for _ in 0..active.len() {
+            let v = active.iter()
+                          .cloned()
+                          .filter(|&v| !added[v])
+                          .max_by_key(|&v| (key[v], cmp::Reverse(v)))
+                          .unwrap();
+            added[v] = true;
+            prev = last;
+            last = v;
+            for &x in &active {
+                if !added[x] {
+                    key[x] += w[v][x];
+                }
+            }
+        }
+
+        // the cut of the phase separates the last vertex from the rest
+        let (s, t) = (prev, last);
+        if best.as_ref().map_or(true, |b| key[t] < b.0) {
+            best = Some((key[t], groups[t].clone()));
+        }
+
+        // merge t into s
+        for x in 0..n {
+            w[s][x] += w[t][x];
+            w[x][s] = w[s][x];
+        }
+        w[s][s] = 0;
+        let t_group = ::std::mem::replace(&mut groups[t], vec![]);
+        groups[s].extend(t_group);
+        active.retain(|&v| v != t);
+    }
+
+    let (weight, mut side) = best.unwrap();
+    side.sort();
+    let mut in_side = vec![false; n];
+    for &v in &side {
+        in_side[v] = true;
+    }
+    let other = (0..n).filter(|&v| !in_side[v]).collect();
+    let crossing_edges = g.edges()
+                             .into_iter()
+                             .filter(|e| in_side[e.either()] != in_side[e.other(e.either())])
+                             .collect();
+
+    WeightedMinCut {
+        weight: weight,
+        sides: (side, other),
+        crossing_edges: crossing_edges,
+    }
+}
 
 impl FromIterator<(u32, Vec<u32>)> for Graph {
     fn from_iter<I: IntoIterator<Item = (u32, Vec<u32>)>>(iter: I) -> Self {
-        Graph { adj: iter.into_iter().collect() }
+        let mut adj = BTreeMap::new();
+        for (u, vs) in iter {
+            let u_to = adj.entry(u).or_insert_with(BTreeMap::new);
+            for v in vs {
+                *u_to.entry(v).or_insert(0) += 1;
+            }
+        }
+        Graph {
+            adj: adj,
+            merged: BTreeMap::new(),
+        }
     }
 }
 
@@ -130,3 +710,239 @@ fn test_min_cut() {
     println!("min cut => {:?}", g.minimum_cut_karger());
 
 }
+
+#[test]
+fn test_min_cut_karger_stein() {
+    // two 5-cliques joined by a single edge
+    let mut adj: Vec<(u32, Vec<u32>)> = vec![];
+    for u in 1..11 {
+        let side = (u - 1) / 5;
+        let vs = (1..11).filter(|&v| v != u && (v - 1) / 5 == side).collect();
+        adj.push((u, vs));
+    }
+    adj[4].1.push(6);
+    adj[5].1.push(5);
+    let g: Graph = adj.into_iter().collect();
+    assert_eq!(g.edges(), 21);
+
+    let mut rng = StdRng::from_seed(&[7_usize][..]);
+    let best = (0..20).map(|_| g.minimum_cut_karger_stein_with(&mut rng).size()).min().unwrap();
+    assert_eq!(best, 1);
+}
+
+#[test]
+fn test_min_cut_partition() {
+    // a square 1-2-3-4 with a pendant vertex 5 hanging off 4
+    let g = Graph::new(vec![1, 2, 3, 4, 5],
+                       vec![vec![2, 4], vec![1, 3], vec![2, 4], vec![3, 1, 5], vec![4]]);
+
+    let mut h = g.clone();
+    h.contract(1, 2);
+    h.contract(3, 4);
+    h.contract(1, 3);
+    assert_eq!(h.members(1), vec![1, 2, 3, 4]);
+
+    let cut = g.cut_of(&h);
+    assert_eq!(cut.size(), 1);
+    assert_eq!(cut.sides, (vec![1, 2, 3, 4], vec![5]));
+    assert_eq!(cut.crossing_edges, vec![(4, 5, 1)]);
+
+    let cut = g.minimum_cut_karger();
+    assert_eq!(cut.sides.0.len() + cut.sides.1.len(), 5);
+    assert!(cut.size() >= 1);
+}
+
+#[test]
+fn test_required_trials() {
+    assert_eq!(required_trials(2, 0.5), 1);
+    assert_eq!(required_trials(4, 0.01), 28);
+    // the n^2 ln n / 2 bound
+    assert_eq!(required_trials(200, 1.0 / 200.0),
+               (19900.0 * 200f64.ln()).ceil() as usize);
+}
+
+#[test]
+fn test_min_cut_trials() {
+    let g = Graph::new(vec![1, 2, 3, 4, 5],
+                       vec![vec![2, 4], vec![1, 3], vec![2, 4], vec![3, 1, 5], vec![4]]);
+
+    let res = g.minimum_cut_trials(42, 0.001);
+    assert_eq!(res.trials, required_trials(5, 0.001));
+    assert_eq!(res.best.size(), 1);
+    assert!(res.found >= 1 && res.found <= res.trials);
+
+    // same seed, same run
+    let again = g.minimum_cut_trials(42, 0.001);
+    assert_eq!(again.best, res.best);
+    assert_eq!(again.found, res.found);
+}
+
+#[test]
+fn test_stoer_wagner() {
+    // example graph from the Stoer-Wagner paper, vertices 1..8 as 0..7
+    let mut g = EdgeWeightedGraph::new(8);
+    for &(u, v, w) in &[(1, 2, 2), (1, 5, 3), (2, 3, 3), (2, 5, 2), (2, 6, 2), (3, 4, 4),
+                        (3, 7, 2), (4, 7, 2), (4, 8, 2), (5, 6, 3), (6, 7, 1), (7, 8, 3)] {
+        g.add_edge(Edge::new(u - 1, v - 1, w));
+    }
+
+    let cut = stoer_wagner_min_cut(&g);
+    assert_eq!(cut.weight, 4);
+    assert_eq!(cut.sides, (vec![2, 3, 6, 7], vec![0, 1, 4, 5]));
+    assert_eq!(cut.crossing_edges.iter().map(|e| e.weight()).sum::<i64>(), 4);
+    assert_eq!(cut.crossing_edges.len(), 2);
+}
+
+#[test]
+fn test_stoer_wagner_validates_karger() {
+    // two 5-cliques joined by two edges
+    let mut adj: Vec<(u32, Vec<u32>)> = vec![];
+    for u in 1..11 {
+        let side = (u - 1) / 5;
+        let vs = (1..11).filter(|&v| v != u && (v - 1) / 5 == side).collect();
+        adj.push((u, vs));
+    }
+    adj[0].1.push(6);
+    adj[5].1.push(1);
+    adj[4].1.push(10);
+    adj[9].1.push(5);
+    let g: Graph = adj.into_iter().collect();
+
+    let (wg, labels) = g.to_edge_weighted();
+    assert_eq!(wg.e(), g.edges());
+    let cut = stoer_wagner_min_cut(&wg);
+    assert_eq!(cut.weight, 2);
+    let side: Vec<u32> = cut.sides.0.iter().map(|&i| labels[i]).collect();
+    assert!(side == vec![1, 2, 3, 4, 5] || side == vec![6, 7, 8, 9, 10]);
+
+    assert_eq!(g.minimum_cut_trials(7, 0.01).best.size() as i64, cut.weight);
+}
+
+#[test]
+fn test_edge_list_min_cut() {
+    let g = Graph::new(vec![1, 2, 3, 4, 5],
+                       vec![vec![2, 4], vec![1, 3], vec![2, 4], vec![3, 1, 5], vec![4]]);
+    let el = g.to_edge_list();
+    assert_eq!(el.vertices(), 5);
+    assert_eq!(el.edges(), g.edges());
+
+    let res = el.minimum_cut_trials(42, 0.001);
+    assert_eq!(res.best.size(), g.minimum_cut_trials(42, 0.001).best.size());
+    assert_eq!(res.best.sides, (vec![1, 2, 3, 4], vec![5]));
+    assert_eq!(res.best.crossing_edges, vec![(4, 5, 1)]);
+
+    let mut rng = StdRng::from_seed(&[1_usize][..]);
+    for _ in 0..20 {
+        let cut = el.minimum_cut_karger_with(&mut rng);
+        assert_eq!(cut.sides.0.len() + cut.sides.1.len(), 5);
+        assert!(cut.sides.0.contains(&1));
+    }
+
+    // two components, the empty cut
+    let g = Graph::new(vec![1, 2, 3, 4], vec![vec![2], vec![1], vec![4], vec![3]]);
+    let cut = g.to_edge_list().minimum_cut_karger_with(&mut rng);
+    assert_eq!(cut.size(), 0);
+    assert_eq!(cut.sides, (vec![1, 2], vec![3, 4]));
+}
+
+#[test]
+fn test_par_min_cut_trials() {
+    let g = Graph::new(vec![1, 2, 3, 4, 5],
+                       vec![vec![2, 4], vec![1, 3], vec![2, 4], vec![3, 1, 5], vec![4]]);
+    let el = g.to_edge_list();
+
+    let res = el.par_minimum_cut_trials(42, 0.001, 4);
+    assert_eq!(res.trials, required_trials(5, 0.001));
+    assert_eq!(res.best.size(), 1);
+    assert!(res.found >= 1 && res.found <= res.trials);
+
+    let again = el.par_minimum_cut_trials(42, 0.001, 4);
+    assert_eq!(again.best, res.best);
+    assert_eq!(again.found, res.found);
+
+    // more workers than trials
+    let res = el.par_minimum_cut_trials(42, 0.5, 64);
+    assert_eq!(res.trials, 7);
+}
+
+#[test]
+fn test_weighted_graph() {
+    // parallel edges in adjacency lists sum up
+    let g = Graph::new(vec![1, 2, 3], vec![vec![2, 2, 3], vec![1, 1], vec![1]]);
+    assert_eq!(g.weight(1, 2), 2);
+    assert_eq!(g.edges(), 3);
+
+    let mut g = Graph::from_weighted_edges(vec![(1, 2, 5), (2, 3, 1), (3, 4, 5), (4, 1, 1),
+                                              (1, 3, 1)]);
+    g.add_edge(3, 1, 2);
+    assert_eq!(g.weight(1, 3), 3);
+    assert_eq!(g.edges(), 15);
+
+    let mut h = g.clone();
+    h.contract(1, 2);
+    assert_eq!(h.weight(1, 3), 4);
+    assert_eq!(h.weight(3, 1), 4);
+    assert_eq!(h.edges(), 10);
+
+    // {1, 2} | {3, 4}: 1 + 1 + 3 = 5, cheaper than any single vertex
+    let res = g.minimum_cut_trials(42, 0.001);
+    assert_eq!(res.best.size(), 5);
+    assert_eq!(res.best.sides, (vec![1, 2], vec![3, 4]));
+    let res = g.to_edge_list().par_minimum_cut_trials(42, 0.001, 2);
+    assert_eq!(res.best.size(), 5);
+    assert_eq!(stoer_wagner_min_cut(&g.to_edge_weighted().0).weight, 5);
+}
+
+#[test]
+fn test_random_edge_by_weight() {
+    let g = Graph::from_weighted_edges(vec![(1, 2, 99), (2, 3, 1)]);
+    let mut rng = StdRng::from_seed(&[3_usize][..]);
+    let heavy = (0..1000)
+                    .map(|_| g.random_edge_with(&mut rng))
+                    .filter(|&(u, v)| (u, v) == (1, 2) || (u, v) == (2, 1))
+                    .count();
+    assert!(heavy > 950);
+}
+
+#[test]
+fn test_graph_validation() {
+    let g = Graph::try_new(vec![1, 2, 3], vec![vec![2, 3], vec![1], vec![1]]).unwrap();
+    assert!(g.validate().is_empty());
+
+    assert_eq!(Graph::try_new(vec![1, 2], vec![vec![2]]).unwrap_err(),
+               GraphError::LengthMismatch {
+                   vertices: 2,
+                   lists: 1,
+               });
+    assert_eq!(Graph::try_new(vec![1, 1], vec![vec![], vec![]]).unwrap_err(),
+               GraphError::DuplicateVertex(1));
+    assert_eq!(Graph::try_new(vec![1, 2], vec![vec![2, 1], vec![1]]).unwrap_err(),
+               GraphError::SelfLoop(1));
+
+    let g = Graph::new(vec![1, 2, 3, 4],
+                       vec![vec![2, 2, 5], vec![1, 3], vec![3], vec![1]]);
+    assert_eq!(g.validate(),
+               vec![GraphError::Asymmetric {
+                        u: 1,
+                        v: 2,
+                        forward: 2,
+                        backward: 1,
+                    },
+                    GraphError::UnknownNeighbor {
+                        vertex: 1,
+                        neighbor: 5,
+                    },
+                    GraphError::Asymmetric {
+                        u: 2,
+                        v: 3,
+                        forward: 1,
+                        backward: 0,
+                    },
+                    GraphError::SelfLoop(3),
+                    GraphError::Asymmetric {
+                        u: 4,
+                        v: 1,
+                        forward: 1,
+                        backward: 0,
+                    }]);
+}
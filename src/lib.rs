@@ -3,12 +3,24 @@
 #![allow(unused_attributes)]
 #![feature(inclusive_range_syntax)]
 
+/// I. INTRODUCTION (Week 1)
+pub mod karatsuba;
+
 /// III. DIVIDE & CONQUER ALGORITHMS (Week 1)
 pub mod inversions;
 
+/// III. CLOSEST PAIR OF POINTS (Week 1)
+pub mod closest_pair;
+
+/// III. STRASSEN'S SUBCUBIC MATRIX MULTIPLICATION (Week 1)
+pub mod strassen;
+
 /// V. QUICKSORT - ALGORITHM (Week 2)
 pub mod qsort;
 
+/// VI. LINEAR-TIME SELECTION (Week 2)
+pub mod select;
+
 /// IX. GRAPHS AND THE CONTRACTION ALGORITHM (Week 3)
 pub mod karger;
 
@@ -21,6 +33,9 @@ pub mod graphs;
 /// XII. HEAPS (Week 5)
 pub mod heaps;
 
+/// Instrumentation shared by the sorting algorithms
+pub mod instrument;
+
 // # Part 2
 
 /// V. PRIM'S MINIMUM SPANNING TREE ALGORITHM (Week 1)
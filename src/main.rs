@@ -29,28 +29,30 @@ fn part1_week1() -> io::Result<()> {
                                 .map(|s| s.trim().parse().unwrap())
                                 .collect();
 
-    println!("got vals -> {:?}",
-             merge_sort_and_count_inversions(&mut vals[..]));
+    println!("got vals -> {:?}", count_inversions(&mut vals[..]));
     Ok(())
 }
 
 fn part1_week2() -> io::Result<()> {
+    use algo::qsort::{quick_sort_with, PivotStrategy};
+
     let mut s = String::new();
     let mut f = try!(File::open("./priv/QuickSort.txt"));
 
     try!(f.read_to_string(&mut s));
-    let mut vals: Vec<i32> = s.lines()
-                              .map(|s| s.trim().parse().unwrap())
-                              .collect();
+    let vals: Vec<i32> = s.lines()
+                          .map(|s| s.trim().parse().unwrap())
+                          .collect();
 
-    println!("got vals -> {:?}", algo::qsort::quick_sort(&mut vals[..]));
+    for &strategy in &[PivotStrategy::First, PivotStrategy::Last, PivotStrategy::MedianOfThree] {
+        let mut vals = vals.clone();
+        println!("{:?} -> {:?}", strategy, quick_sort_with(&mut vals[..], strategy));
+    }
     Ok(())
 }
 
 /// Run the randomized contraction algorithm for the min cut problem and
 /// use it on the above graph to compute the min cut.
-/// Note:
-/// > cargo run | sort -n | uniq -c | sort -n -r | head
 fn part1_week3() -> io::Result<()> {
     let mut s = String::new();
     let mut f = try!(File::open("./priv/kargerMinCut.txt"));
@@ -67,9 +69,12 @@ fn part1_week3() -> io::Result<()> {
              .map(|uvs| (uvs[0], uvs[1..].to_owned()))
              .collect::<algo::karger::Graph>();
 
-    for _ in 0..500 {
-        println!("{:?}", g.minimum_cut_karger().edges());
-    }
+    let n = g.vertices();
+    let res = g.to_edge_list().par_minimum_cut_trials(42, 1.0 / n as f64, 4);
+    println!("min cut => {} (found {} times in {} trials)",
+             res.best.size(),
+             res.found,
+             res.trials);
     Ok(())
 }
 
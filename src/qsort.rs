@@ -8,17 +8,129 @@
 // according to those terms.
 
 
-pub fn quick_sort(arr: &mut [i32]) -> usize {
+extern crate rand;
+
+use std::cmp;
+use std::cmp::Ordering;
+use std::thread;
+
+use self::rand::{Rng, SeedableRng, StdRng};
+
+use super::heaps::heap_sort_by;
+use super::instrument::SortObserver;
+
+
+/// How `quick_sort_with` picks the pivot element of each subarray.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PivotStrategy {
+    /// Always the first element.
+    First,
+    /// Always the last element.
+    Last,
+    /// Median of the first, middle and last elements.
+    MedianOfThree,
+    /// A uniformly random element, seeded for reproducible runs.
+    Random(usize),
+    /// Tukey's ninther, the median of three medians of three.
+    Ninther,
+}
+
+impl Default for PivotStrategy {
+    fn default() -> PivotStrategy {
+        PivotStrategy::MedianOfThree
+    }
+}
+
+struct PivotChooser {
+    strategy: PivotStrategy,
+    rng: Option<StdRng>,
+}
+
+impl PivotChooser {
+    fn new(strategy: PivotStrategy) -> PivotChooser {
+        let rng = match strategy {
+            PivotStrategy::Random(seed) => Some(StdRng::from_seed(&[seed][..])),
+            _ => None,
+        };
+        PivotChooser {
+            strategy: strategy,
+            rng: rng,
+        }
+    }
+
+    fn choose<T, F>(&mut self, arr: &[T], compare: &mut F) -> usize
+        where F: FnMut(&T, &T) -> Ordering
+    {
+        match self.strategy {
+            PivotStrategy::First => 0,
+            PivotStrategy::Last => arr.len() - 1,
+            PivotStrategy::MedianOfThree => median_of_three_by(arr, compare),
+            PivotStrategy::Random(_) => self.rng.as_mut().unwrap().gen_range(0, arr.len()),
+            PivotStrategy::Ninther => ninther_by(arr, compare),
+        }
+    }
+}
+
+
+pub fn quick_sort<T: Ord>(arr: &mut [T]) -> usize {
+    quick_sort_by(arr, |a, b| a.cmp(b))
+}
+
+/// Quick sort with a custom comparator, returns the number of comparisons.
+pub fn quick_sort_by<T, F>(arr: &mut [T], mut compare: F) -> usize
+    where F: FnMut(&T, &T) -> Ordering
+{
+    let mut chooser = PivotChooser::new(PivotStrategy::default());
+    quick_sort_by_ref(arr, &mut chooser, &mut compare, &mut (), 1)
+}
+
+/// Quick sort using the given pivot strategy, returns the number of comparisons.
+pub fn quick_sort_with<T: Ord>(arr: &mut [T], strategy: PivotStrategy) -> usize {
+    quick_sort_observed(arr, strategy, &mut ())
+}
+
+/// Quick sort reporting exact comparisons, swaps, recursion depth and
+/// partition sizes to `observer`, e.g. a `SortStats`.
+///
+/// Returns the same m-1 comparison count as `quick_sort_with`.
+pub fn quick_sort_observed<T, O>(arr: &mut [T], strategy: PivotStrategy, observer: &mut O) -> usize
+    where T: Ord,
+          O: SortObserver
+{
+    let mut chooser = PivotChooser::new(strategy);
+    quick_sort_by_ref(arr,
+                      &mut chooser,
+                      &mut |a: &T, b: &T| a.cmp(b),
+                      observer,
+                      1)
+}
+
+fn quick_sort_by_ref<T, F, O>(arr: &mut [T],
+                              chooser: &mut PivotChooser,
+                              compare: &mut F,
+                              observer: &mut O,
+                              depth: usize)
+                              -> usize
+    where F: FnMut(&T, &T) -> Ordering,
+          O: SortObserver
+{
     if arr.len() <= 1 {
         return 0;
     }
-    let pivot = choose_pivot(arr);
+    observer.on_recurse(depth);
+    let pivot = chooser.choose(arr,
+                               &mut |a: &T, b: &T| {
+                                   observer.on_compare();
+                                   compare(a, b)
+                               });
     if pivot != 0 {
         arr.swap(0, pivot);
+        observer.on_swap();
     }
-    let mid = inplace_partition(arr);
-    let x = quick_sort(&mut arr[..mid - 1]);
-    let y = quick_sort(&mut arr[mid..]);
+    observer.on_partition(arr.len());
+    let mid = partition_observed(arr, compare, observer);
+    let x = quick_sort_by_ref(&mut arr[..mid - 1], chooser, compare, observer, depth + 1);
+    let y = quick_sort_by_ref(&mut arr[mid..], chooser, compare, observer, depth + 1);
     // when there is a recursive call on a subarray of length m,
     // you should simply add m−1 to your running total of comparisons.
     // (This is because the pivot element is compared to each of
@@ -26,50 +138,295 @@ pub fn quick_sort(arr: &mut [i32]) -> usize {
     arr.len() - 1 + x + y
 }
 
+/// Parallel quick sort, the two sides of a partition are sorted on separate
+/// scoped threads when both have at least `threshold` items. Forks nest at
+/// most `log2(available_parallelism)` deep, below that it sorts sequentially.
+///
+/// Uses the median-of-three pivot, so the comparison count is exactly the
+/// one `quick_sort` reports.
+pub fn par_quick_sort<T: Ord + Send>(arr: &mut [T], threshold: usize) -> usize {
+    par_quick_sort_by(arr, threshold, |a, b| a.cmp(b))
+}
+
+pub fn par_quick_sort_by<T, F>(arr: &mut [T], threshold: usize, compare: F) -> usize
+    where T: Send,
+          F: Fn(&T, &T) -> Ordering + Sync
+{
+    let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    par_quick_sort_by_ref(arr, threshold, log2(threads), &compare)
+}
+
+// forks: how many more levels may still spawn a thread
+fn par_quick_sort_by_ref<T, F>(arr: &mut [T], threshold: usize, forks: usize, compare: &F) -> usize
+    where T: Send,
+          F: Fn(&T, &T) -> Ordering + Sync
+{
+    let mut compare_mut = |a: &T, b: &T| compare(a, b);
+    if arr.len() < threshold || arr.len() <= 1 || forks == 0 {
+        let mut chooser = PivotChooser::new(PivotStrategy::MedianOfThree);
+        return quick_sort_by_ref(arr, &mut chooser, &mut compare_mut, &mut (), 1);
+    }
+    let pivot = median_of_three_by(arr, &mut compare_mut);
+    if pivot != 0 {
+        arr.swap(0, pivot);
+    }
+    let mid = inplace_partition_by(arr, &mut compare_mut);
+    let n = arr.len();
+
+    let (left, right) = arr.split_at_mut(mid);
+    let left = &mut left[..mid - 1];
+    // a side that is short or already sorted is not worth a thread, the long
+    // one keeps the budget
+    let min_fork = cmp::max(threshold, 2);
+    if left.len() < min_fork || right.len() < min_fork {
+        let x = par_quick_sort_by_ref(left, threshold, forks, compare);
+        let y = par_quick_sort_by_ref(right, threshold, forks, compare);
+        return n - 1 + x + y;
+    }
+    let (x, y) = thread::scope(|s| {
+        let handle = s.spawn(|| par_quick_sort_by_ref(left, threshold, forks - 1, compare));
+        let y = par_quick_sort_by_ref(right, threshold, forks - 1, compare);
+        (handle.join().unwrap(), y)
+    });
+    n - 1 + x + y
+}
+
+/// Subarrays shorter than this are finished by insertion sort in `intro_sort`.
+const INSERTION_SORT_THRESHOLD: usize = 16;
+
+/// Introspective sort: quick sort that falls back to heap sort once the
+/// recursion depth exceeds `2 * log2(n)`, and to insertion sort for short
+/// subarrays, so the worst case stays O(n log n).
+///
+/// Partitioning steps are counted like `quick_sort` (m-1 per subarray),
+/// heap sort and insertion sort steps count every comparison made.
+pub fn intro_sort<T: Ord>(arr: &mut [T]) -> usize {
+    intro_sort_by(arr, |a, b| a.cmp(b))
+}
+
+pub fn intro_sort_by<T, F>(arr: &mut [T], mut compare: F) -> usize
+    where F: FnMut(&T, &T) -> Ordering
+{
+    let mut chooser = PivotChooser::new(PivotStrategy::default());
+    let depth_limit = 2 * log2(arr.len());
+    intro_sort_by_ref(arr, depth_limit, &mut chooser, &mut compare)
+}
+
+fn intro_sort_by_ref<T, F>(arr: &mut [T],
+                           depth_limit: usize,
+                           chooser: &mut PivotChooser,
+                           compare: &mut F)
+                           -> usize
+    where F: FnMut(&T, &T) -> Ordering
+{
+    if arr.len() < INSERTION_SORT_THRESHOLD {
+        return insertion_sort_by(arr, compare);
+    }
+    if depth_limit == 0 {
+        return heap_sort_by(arr, &mut *compare);
+    }
+    let pivot = chooser.choose(arr, compare);
+    if pivot != 0 {
+        arr.swap(0, pivot);
+    }
+    let mid = inplace_partition_by(arr, compare);
+    let x = intro_sort_by_ref(&mut arr[..mid - 1], depth_limit - 1, chooser, compare);
+    let y = intro_sort_by_ref(&mut arr[mid..], depth_limit - 1, chooser, compare);
+    arr.len() - 1 + x + y
+}
+
+// floor(log2(n)), 0 for n <= 1
+fn log2(n: usize) -> usize {
+    let mut n = n;
+    let mut k = 0;
+    while n > 1 {
+        n >>= 1;
+        k += 1;
+    }
+    k
+}
 
+/// Insertion sort, returns the number of comparisons.
+pub fn insertion_sort_by<T, F>(arr: &mut [T], compare: &mut F) -> usize
+    where F: FnMut(&T, &T) -> Ordering
+{
+    let mut cnt = 0;
+    for i in 1..arr.len() {
+        let mut j = i;
+        while j > 0 {
+            cnt += 1;
+            if compare(&arr[j], &arr[j - 1]) != Ordering::Less {
+                break;
+            }
+            arr.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+    cnt
+}
+
+/// Quick sort with three-way partitioning, suits inputs with many duplicated keys.
+pub fn quick_sort_3way<T: Ord>(arr: &mut [T]) -> usize {
+    quick_sort_3way_by(arr, |a, b| a.cmp(b))
+}
 
-fn choose_pivot(arr: &[i32]) -> usize {
-    // 0
-    // arr.len() - 1
-    median_of_three(arr)
+pub fn quick_sort_3way_by<T, F>(arr: &mut [T], mut compare: F) -> usize
+    where F: FnMut(&T, &T) -> Ordering
+{
+    let mut chooser = PivotChooser::new(PivotStrategy::default());
+    quick_sort_3way_by_ref(arr, &mut chooser, &mut compare)
+}
+
+fn quick_sort_3way_by_ref<T, F>(arr: &mut [T],
+                                chooser: &mut PivotChooser,
+                                compare: &mut F)
+                                -> usize
+    where F: FnMut(&T, &T) -> Ordering
+{
+    if arr.len() <= 1 {
+        return 0;
+    }
+    let pivot = chooser.choose(arr, compare);
+    if pivot != 0 {
+        arr.swap(0, pivot);
+    }
+    let (lt, gt) = three_way_partition_by(arr, compare);
+    // keys equal to the pivot are already in place, never recurse on them
+    let x = quick_sort_3way_by_ref(&mut arr[..lt], chooser, compare);
+    let y = quick_sort_3way_by_ref(&mut arr[gt..], chooser, compare);
+    // same accounting as `quick_sort`: m-1 for a subarray of length m
+    arr.len() - 1 + x + y
 }
 
 
-fn median_of_three(arr: &[i32]) -> usize {
+#[allow(dead_code)]
+fn median_of_three<T: Ord>(arr: &[T]) -> usize {
+    median_of_three_by(arr, &mut |a: &T, b: &T| a.cmp(b))
+}
+
+fn median_of_three_by<T, F>(arr: &[T], compare: &mut F) -> usize
+    where F: FnMut(&T, &T) -> Ordering
+{
     let first = 0;
     // IMPORTANT: middle of array
     let middle = (arr.len() + 1) / 2 - 1;
     let last = arr.len() - 1;
-    let a = arr[first];
-    let b = arr[middle];
-    let c = arr[last];
-
-    if (a - b) * (b - c) >= 0 {
-        middle
-    } else if (a - b) * (a - c) <= 0 {
-        first
+    median_index_of(arr, first, middle, last, compare)
+}
+
+/// Median of nine elements sampled across the array, falls back to
+/// median of three for short arrays.
+fn ninther_by<T, F>(arr: &[T], compare: &mut F) -> usize
+    where F: FnMut(&T, &T) -> Ordering
+{
+    let n = arr.len();
+    if n < 9 {
+        return median_of_three_by(arr, compare);
+    }
+    let step = n / 8;
+    let middle = (n + 1) / 2 - 1;
+    let last = n - 1;
+
+    let lo = median_index_of(arr, 0, step, 2 * step, compare);
+    let mi = median_index_of(arr, middle - step, middle, middle + step, compare);
+    let hi = median_index_of(arr, last - 2 * step, last - step, last, compare);
+    median_index_of(arr, lo, mi, hi, compare)
+}
+
+// index of the median among arr[i], arr[j], arr[k]
+fn median_index_of<T, F>(arr: &[T], i: usize, j: usize, k: usize, compare: &mut F) -> usize
+    where F: FnMut(&T, &T) -> Ordering
+{
+    let a = &arr[i];
+    let b = &arr[j];
+    let c = &arr[k];
+
+    // compare orderings instead of `(a - b) * (b - c)`, which overflows
+    let ab = compare(a, b);
+    let bc = compare(b, c);
+    if ab == Ordering::Equal || bc == Ordering::Equal || ab == bc {
+        j
+    } else if ab != compare(a, c) {
+        i
     } else {
-        last
+        k
     }
 }
 
-// assume pivot is first
-fn inplace_partition(arr: &mut [i32]) -> usize {
+/// Lomuto partition around the first element, returns the index right after
+/// the pivot's final position.
+pub fn inplace_partition<T: Ord>(arr: &mut [T]) -> usize {
+    inplace_partition_by(arr, &mut |a: &T, b: &T| a.cmp(b))
+}
+
+pub fn inplace_partition_by<T, F>(arr: &mut [T], compare: &mut F) -> usize
+    where F: FnMut(&T, &T) -> Ordering
+{
+    partition_observed(arr, compare, &mut ())
+}
+
+fn partition_observed<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O) -> usize
+    where F: FnMut(&T, &T) -> Ordering,
+          O: SortObserver
+{
     let pivot = 0;
     let n = arr.len();
-    let p = arr[pivot];
     let mut i = pivot + 1;
 
     for j in pivot + 1..n {
-        if arr[j] < p {
-            arr.swap(j, i);
+        observer.on_compare();
+        if compare(&arr[j], &arr[pivot]) == Ordering::Less {
+            // only count swaps that move something
+            if j != i {
+                arr.swap(j, i);
+                observer.on_swap();
+            }
             i += 1
         }
     }
-    arr.swap(pivot, i - 1);
+    if i - 1 != pivot {
+        arr.swap(pivot, i - 1);
+        observer.on_swap();
+    }
     i
 }
 
+/// Dijkstra's three-way (Dutch national flag) partition, pivot is first.
+///
+/// Returns `(lt, gt)` so that `arr[..lt]` is less than the pivot,
+/// `arr[lt..gt]` equals the pivot and `arr[gt..]` is greater.
+pub fn three_way_partition<T: Ord>(arr: &mut [T]) -> (usize, usize) {
+    three_way_partition_by(arr, &mut |a: &T, b: &T| a.cmp(b))
+}
+
+pub fn three_way_partition_by<T, F>(arr: &mut [T], compare: &mut F) -> (usize, usize)
+    where F: FnMut(&T, &T) -> Ordering
+{
+    if arr.is_empty() {
+        return (0, 0);
+    }
+    let mut lt = 0;
+    let mut i = 1;
+    let mut gt = arr.len();
+
+    // arr[lt..i] are all equal to the pivot, so arr[lt] is the pivot.
+    while i < gt {
+        match compare(&arr[i], &arr[lt]) {
+            Ordering::Less => {
+                arr.swap(lt, i);
+                lt += 1;
+                i += 1;
+            }
+            Ordering::Greater => {
+                gt -= 1;
+                arr.swap(i, gt);
+            }
+            Ordering::Equal => i += 1,
+        }
+    }
+    (lt, gt)
+}
+
 
 #[test]
 fn test_inplace_partion() {
@@ -92,3 +449,170 @@ fn test_mid_of_three() {
     assert_eq!(median_of_three(&[8, 2, 4, 5, 7, 1]), 2);
     assert_eq!(median_of_three(&[1, 3, 8]), 1);
 }
+
+#[test]
+fn test_mid_of_three_no_overflow() {
+    let max = i32::max_value();
+    let min = i32::min_value();
+    assert_eq!(median_of_three(&[min, 0, max]), 1);
+    assert_eq!(median_of_three(&[max, min, 0]), 2);
+    assert_eq!(median_of_three(&[0, max, min]), 0);
+}
+
+#[test]
+fn test_quick_sort_generic() {
+    let mut v = vec!["pear", "apple", "fig", "banana"];
+    quick_sort(&mut v[..]);
+    assert_eq!(&v, &["apple", "banana", "fig", "pear"]);
+
+    let mut v: Vec<i64> = vec![3, 8, 2, 5, 1, 4, 7, 6];
+    let cmp_cnt = quick_sort_by(&mut v[..], |a, b| b.cmp(a));
+    assert_eq!(&v, &[8, 7, 6, 5, 4, 3, 2, 1]);
+    assert_eq!(cmp_cnt, 13);
+}
+
+#[test]
+fn test_quick_sort_pivot_strategies() {
+    // from the course forums
+    let case = vec![3, 9, 8, 4, 6, 10, 2, 5, 7, 1];
+
+    let mut v = case.clone();
+    assert_eq!(quick_sort_with(&mut v[..], PivotStrategy::First), 25);
+    let mut v = case.clone();
+    assert_eq!(quick_sort_with(&mut v[..], PivotStrategy::Last), 29);
+    let mut v = case.clone();
+    assert_eq!(quick_sort_with(&mut v[..], PivotStrategy::MedianOfThree), 21);
+
+    for &strategy in &[PivotStrategy::Random(42), PivotStrategy::Ninther] {
+        let mut v: Vec<i32> = (0..1000).map(|i| (i * 7919) % 1000).collect();
+        quick_sort_with(&mut v[..], strategy);
+        assert_eq!(v, (0..1000).collect::<Vec<i32>>());
+    }
+
+    // same seed, same pivots
+    let mut v = case.clone();
+    let mut w = case.clone();
+    assert_eq!(quick_sort_with(&mut v[..], PivotStrategy::Random(7)),
+               quick_sort_with(&mut w[..], PivotStrategy::Random(7)));
+}
+
+#[test]
+fn test_three_way_partition() {
+    let mut v = vec![3, 8, 3, 5, 1, 3, 7, 2];
+    let (lt, gt) = three_way_partition(&mut v[..]);
+    assert_eq!((lt, gt), (2, 5));
+    assert!(v[..lt].iter().all(|&x| x < 3));
+    assert!(v[lt..gt].iter().all(|&x| x == 3));
+    assert!(v[gt..].iter().all(|&x| x > 3));
+
+    assert_eq!(three_way_partition::<i32>(&mut []), (0, 0));
+}
+
+#[test]
+fn test_quick_sort_3way() {
+    let mut v = vec![3, 8, 2, 5, 1, 4, 7, 6];
+    let cmp_cnt = quick_sort_3way(&mut v[..]);
+    assert_eq!(&v, &[1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(cmp_cnt, 13);
+
+    // all keys equal, a single partition pass
+    let mut v = vec![1; 10000];
+    assert_eq!(quick_sort_3way(&mut v[..]), 9999);
+
+    let mut v: Vec<u8> = (0..1000).map(|i| (i % 5) as u8).collect();
+    quick_sort_3way_by(&mut v[..], |a, b| b.cmp(a));
+    assert!(v.windows(2).all(|w| w[0] >= w[1]));
+}
+
+#[test]
+fn test_intro_sort() {
+    let mut v = vec![3, 8, 2, 5, 1, 4, 7, 6];
+    intro_sort(&mut v[..]);
+    assert_eq!(&v, &[1, 2, 3, 4, 5, 6, 7, 8]);
+
+    // a long reversed run, median-of-three keeps its partitions balanced
+    let mut v: Vec<i64> = (0..100000).collect();
+    v.reverse();
+    intro_sort_by(&mut v[..], |a, b| a.cmp(b));
+    assert!(v.windows(2).all(|w| w[0] <= w[1]));
+
+    let mut v: Vec<u32> = (0..5000).map(|i| (i * 7919) % 97).collect();
+    let mut expected = v.clone();
+    expected.sort();
+    intro_sort(&mut v[..]);
+    assert_eq!(v, expected);
+
+    // all equal: every Lomuto partition splits off just the pivot, so the
+    // depth limit is hit and heap sort finishes the rest
+    let n = 20000;
+    let mut v = vec![7_u8; n];
+    let cmp_cnt = intro_sort(&mut v[..]);
+    assert!(cmp_cnt < 100 * n, "{} comparisons", cmp_cnt);
+    assert!(v.iter().all(|&x| x == 7));
+
+    // with no depth left it is a plain heap sort
+    let mut v: Vec<u32> = (0..5000).map(|i| (i * 7919) % 97).collect();
+    let mut w = v.clone();
+    let mut chooser = PivotChooser::new(PivotStrategy::default());
+    let cmp_cnt = intro_sort_by_ref(&mut v[..], 0, &mut chooser, &mut |a: &u32, b: &u32| a.cmp(b));
+    assert_eq!(cmp_cnt, heap_sort_by(&mut w[..], &mut |a: &u32, b: &u32| a.cmp(b)));
+    assert_eq!(v, expected);
+}
+
+#[test]
+fn test_log2() {
+    assert_eq!(log2(0), 0);
+    assert_eq!(log2(1), 0);
+    assert_eq!(log2(8), 3);
+    assert_eq!(log2(1000), 9);
+}
+
+#[test]
+fn test_par_quick_sort() {
+    let v: Vec<i64> = (0..200000).map(|i| (i * 7919) % 100003).collect();
+    let mut expected = v.clone();
+    let cmp_cnt = quick_sort(&mut expected[..]);
+
+    for &threshold in &[1000, 1000000] {
+        let mut w = v.clone();
+        assert_eq!(par_quick_sort(&mut w[..], threshold), cmp_cnt);
+        assert_eq!(w, expected);
+    }
+
+    // every recursive call may fork, within the budget
+    let mut w = vec![3, 8, 2, 5, 1, 4, 7, 6];
+    assert_eq!(par_quick_sort(&mut w[..], 0), 13);
+    assert_eq!(&w, &[1, 2, 3, 4, 5, 6, 7, 8]);
+    let mut w = v.clone();
+    assert_eq!(par_quick_sort_by_ref(&mut w[..], 0, 3, &|a: &i64, b: &i64| a.cmp(b)),
+               cmp_cnt);
+    assert_eq!(w, expected);
+
+    let mut w = vec!["pear", "apple", "fig"];
+    par_quick_sort_by(&mut w[..], 2, |a, b| b.cmp(a));
+    assert_eq!(&w, &["pear", "fig", "apple"]);
+}
+
+#[test]
+fn test_quick_sort_observed() {
+    use super::instrument::SortStats;
+
+    let mut v = vec![3, 8, 2, 5, 1, 4, 7, 6];
+    let mut stats = SortStats::new();
+    let cmp_cnt = quick_sort_observed(&mut v[..], PivotStrategy::MedianOfThree, &mut stats);
+    assert_eq!(&v, &[1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(cmp_cnt, 13);
+    // median of three takes 2 or 3 comparisons on top of the partitions
+    assert!(stats.comparisons > cmp_cnt as u64);
+    // no-op swaps of an item with itself are not counted
+    assert_eq!(stats.swaps, 12);
+    assert!(stats.max_depth >= 3);
+    assert_eq!(stats.partition_sizes[&3], 1);
+
+    // first pivot on sorted input is quadratic and n levels deep
+    let mut v: Vec<u32> = (0..100).collect();
+    let mut stats = SortStats::new();
+    let cmp_cnt = quick_sort_observed(&mut v[..], PivotStrategy::First, &mut stats);
+    assert_eq!(stats.comparisons, cmp_cnt as u64);
+    assert_eq!(stats.max_depth, 99);
+}
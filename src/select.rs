@@ -0,0 +1,217 @@
+//! Randomized and deterministic selection of the i-th order statistic.
+
+extern crate rand;
+
+use std::cmp::Ordering;
+
+use self::rand::Rng;
+
+use super::qsort::{insertion_sort_by, three_way_partition_by};
+
+
+/// RSelect, finds the k-th smallest (0-based) item in expected linear time.
+///
+/// Reorders `arr`, returns the item and the number of comparisons made.
+pub fn select_kth<T: Ord>(arr: &mut [T], k: usize) -> (&T, usize) {
+    select_kth_by(arr, k, |a, b| a.cmp(b))
+}
+
+pub fn select_kth_by<T, F>(arr: &mut [T], k: usize, compare: F) -> (&T, usize)
+    where F: FnMut(&T, &T) -> Ordering
+{
+    select_kth_by_with(arr, k, &mut rand::thread_rng(), compare)
+}
+
+/// RSelect with pivots drawn from `rng`, reproducible with a seeded one.
+pub fn select_kth_with<'a, T, R>(arr: &'a mut [T], k: usize, rng: &mut R) -> (&'a T, usize)
+    where T: Ord,
+          R: Rng
+{
+    select_kth_by_with(arr, k, rng, |a, b| a.cmp(b))
+}
+
+pub fn select_kth_by_with<'a, T, F, R>(arr: &'a mut [T],
+                                       k: usize,
+                                       rng: &mut R,
+                                       mut compare: F)
+                                       -> (&'a T, usize)
+    where F: FnMut(&T, &T) -> Ordering,
+          R: Rng
+{
+    assert!(k < arr.len(), "order statistic out of bounds");
+    let mut cnt = 0;
+    let idx = {
+        let mut counted = |a: &T, b: &T| {
+            cnt += 1;
+            compare(a, b)
+        };
+        r_select(arr, k, rng, &mut counted)
+    };
+    (&arr[idx], cnt)
+}
+
+/// DSelect, median of medians, finds the k-th smallest (0-based) item in
+/// worst-case linear time.
+///
+/// Reorders `arr`, returns the item and the number of comparisons made.
+pub fn select_kth_deterministic<T: Ord>(arr: &mut [T], k: usize) -> (&T, usize) {
+    select_kth_deterministic_by(arr, k, |a, b| a.cmp(b))
+}
+
+pub fn select_kth_deterministic_by<T, F>(arr: &mut [T], k: usize, mut compare: F) -> (&T, usize)
+    where F: FnMut(&T, &T) -> Ordering
+{
+    assert!(k < arr.len(), "order statistic out of bounds");
+    let mut cnt = 0;
+    let idx = {
+        let mut counted = |a: &T, b: &T| {
+            cnt += 1;
+            compare(a, b)
+        };
+        d_select(arr, k, &mut counted)
+    };
+    (&arr[idx], cnt)
+}
+
+// returns the index of the k-th item, once it is in place
+fn r_select<T, F, R>(arr: &mut [T], k: usize, rng: &mut R, compare: &mut F) -> usize
+    where F: FnMut(&T, &T) -> Ordering,
+          R: Rng
+{
+    let mut lo = 0;
+    let mut hi = arr.len();
+    let mut k = k;
+
+    loop {
+        let sub = &mut arr[lo..hi];
+        if sub.len() == 1 {
+            return lo;
+        }
+        let pivot = rng.gen_range(0, sub.len());
+        sub.swap(0, pivot);
+        // three-way, so that duplicates of the pivot are done with at once
+        let (lt, gt) = three_way_partition_by(sub, compare);
+        if k < lt {
+            hi = lo + lt;
+        } else if k < gt {
+            return lo + k;
+        } else {
+            lo += gt;
+            k -= gt;
+        }
+    }
+}
+
+fn d_select<T, F>(arr: &mut [T], k: usize, compare: &mut F) -> usize
+    where F: FnMut(&T, &T) -> Ordering
+{
+    let mut lo = 0;
+    let mut hi = arr.len();
+    let mut k = k;
+
+    loop {
+        let sub = &mut arr[lo..hi];
+        let n = sub.len();
+        if n <= 5 {
+            insertion_sort_by(sub, compare);
+            return lo + k;
+        }
+
+        // sort each group of 5, gather the group medians to the front
+        let mut g = 0;
+        let mut i = 0;
+        while i < n {
+            let end = if i + 5 < n { i + 5 } else { n };
+            insertion_sort_by(&mut sub[i..end], compare);
+            sub.swap(g, i + (end - i - 1) / 2);
+            g += 1;
+            i = end;
+        }
+
+        // median of medians is the pivot
+        let mm = d_select(&mut sub[..g], (g - 1) / 2, compare);
+        sub.swap(0, mm);
+        let (lt, gt) = three_way_partition_by(sub, compare);
+        if k < lt {
+            hi = lo + lt;
+        } else if k < gt {
+            return lo + k;
+        } else {
+            lo += gt;
+            k -= gt;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use self::super::*;
+
+    #[test]
+    fn test_select_kth() {
+        let v = vec![3, 8, 2, 5, 1, 4, 7, 6];
+        for k in 0..v.len() {
+            let mut w = v.clone();
+            assert_eq!(*select_kth(&mut w[..], k).0, k as i32 + 1);
+            let mut w = v.clone();
+            assert_eq!(*select_kth_deterministic(&mut w[..], k).0, k as i32 + 1);
+        }
+
+        let mut w = vec!["b", "a", "c"];
+        assert_eq!(*select_kth_by(&mut w[..], 0, |a, b| b.cmp(a)).0, "c");
+    }
+
+    #[test]
+    fn test_select_kth_large() {
+        let v: Vec<u64> = (0..10007).map(|i| (i * 7919) % 1000).collect();
+        let mut sorted = v.clone();
+        sorted.sort();
+
+        for &k in &[0, 1, 4999, 5003, 9900, 10006] {
+            let mut w = v.clone();
+            assert_eq!(*select_kth(&mut w[..], k).0, sorted[k]);
+
+            let mut w = v.clone();
+            let (kth, cnt) = select_kth_deterministic(&mut w[..], k);
+            assert_eq!(*kth, sorted[k]);
+            assert!(cnt > 0);
+        }
+    }
+
+    #[test]
+    fn test_select_kth_duplicates() {
+        use super::rand::{SeedableRng, StdRng};
+
+        let n = 20000;
+        for v in vec![vec![7_u32; n], (0..n as u32).map(|i| i % 3).collect()] {
+            let mut sorted = v.clone();
+            sorted.sort();
+            for &k in &[0, n / 2, n - 1] {
+                let mut w = v.clone();
+                let mut rng = StdRng::from_seed(&[k][..]);
+                let (kth, cnt) = select_kth_with(&mut w[..], k, &mut rng);
+                assert_eq!(*kth, sorted[k]);
+                assert!(cnt <= 3 * n, "{} comparisons", cnt);
+
+                let mut w = v.clone();
+                let (kth, cnt) = select_kth_deterministic(&mut w[..], k);
+                assert_eq!(*kth, sorted[k]);
+                assert!(cnt <= 10 * n, "{} comparisons", cnt);
+            }
+        }
+    }
+
+    #[test]
+    fn test_select_kth_seeded() {
+        use super::rand::{SeedableRng, StdRng};
+
+        let v: Vec<u64> = (0..1000).map(|i| (i * 7919) % 1009).collect();
+        let mut w1 = v.clone();
+        let mut w2 = v.clone();
+        let r1 = select_kth_with(&mut w1[..], 500, &mut StdRng::from_seed(&[42_usize][..])).1;
+        let r2 = select_kth_with(&mut w2[..], 500, &mut StdRng::from_seed(&[42_usize][..])).1;
+        assert_eq!(r1, r2);
+        assert_eq!(w1, w2);
+    }
+}
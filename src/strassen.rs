@@ -0,0 +1,291 @@
+//! Strassen's subcubic matrix multiplication.
+
+use std::cmp;
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+/// Products with a dimension smaller than this are computed by the naive algorithm.
+pub const STRASSEN_CUTOFF: usize = 64;
+
+
+/// A dense, row-major matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T> Matrix<T>
+    where T: Copy + Default + Add<Output = T> + Sub<Output = T> + Mul<Output = T>
+{
+    /// A zero matrix.
+    pub fn new(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix {
+            rows: rows,
+            cols: cols,
+            data: vec![T::default(); rows * cols],
+        }
+    }
+
+    /// A matrix from its items in row-major order.
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<T>) -> Matrix<T> {
+        assert_eq!(data.len(), rows * cols, "matrix size mismatch");
+        Matrix {
+            rows: rows,
+            cols: cols,
+            data: data,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The O(n^3) definition.
+    pub fn naive_mul(&self, other: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.cols, other.rows, "matrix dimensions mismatch");
+        let mut res = Matrix::new(self.rows, other.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self[(i, k)];
+                for j in 0..other.cols {
+                    res[(i, j)] = res[(i, j)] + a * other[(k, j)];
+                }
+            }
+        }
+        res
+    }
+
+    /// Strassen's algorithm in O(n^2.81), naive once a dimension is below
+    /// `STRASSEN_CUTOFF`. Odd dimensions are zero padded by one at each level.
+    pub fn strassen_mul(&self, other: &Matrix<T>) -> Matrix<T> {
+        self.strassen_mul_with_cutoff(other, STRASSEN_CUTOFF)
+    }
+
+    pub fn strassen_mul_with_cutoff(&self, other: &Matrix<T>, cutoff: usize) -> Matrix<T> {
+        assert_eq!(self.cols, other.rows, "matrix dimensions mismatch");
+        let data = strassen(&self.data,
+                            &other.data,
+                            (self.rows, self.cols, other.cols),
+                            cutoff);
+        Matrix::from_vec(self.rows, other.cols, data)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Matrix<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                if j > 0 {
+                    try!(write!(f, " "));
+                }
+                try!(write!(f, "{}", self.data[i * self.cols + j]));
+            }
+            try!(writeln!(f, ""));
+        }
+        Ok(())
+    }
+}
+
+
+// a * b for the m x k matrix a and k x n matrix b, row-major
+fn strassen<T>(a: &[T], b: &[T], (m, k, n): (usize, usize, usize), cutoff: usize) -> Vec<T>
+    where T: Copy + Default + Add<Output = T> + Sub<Output = T> + Mul<Output = T>
+{
+    let min_dim = cmp::min(m, cmp::min(k, n));
+    if min_dim <= 1 || min_dim < cutoff {
+        let mut c = vec![T::default(); m * n];
+        for i in 0..m {
+            for l in 0..k {
+                let x = a[i * k + l];
+                for j in 0..n {
+                    c[i * n + j] = c[i * n + j] + x * b[l * n + j];
+                }
+            }
+        }
+        return c;
+    }
+
+    // half of each dimension, rounded up: the blocks of an odd dimension are
+    // zero padded by one
+    let (hm, hk, hn) = ((m + 1) / 2, (k + 1) / 2, (n + 1) / 2);
+    let (a11, a12, a21, a22) = (block(a, (m, k), (0, 0), (hm, hk)),
+                                block(a, (m, k), (0, hk), (hm, hk)),
+                                block(a, (m, k), (hm, 0), (hm, hk)),
+                                block(a, (m, k), (hm, hk), (hm, hk)));
+    let (b11, b12, b21, b22) = (block(b, (k, n), (0, 0), (hk, hn)),
+                                block(b, (k, n), (0, hn), (hk, hn)),
+                                block(b, (k, n), (hk, 0), (hk, hn)),
+                                block(b, (k, n), (hk, hn), (hk, hn)));
+
+    // the seven products
+    let h = (hm, hk, hn);
+    let p1 = strassen(&a11, &sub(&b12, &b22), h, cutoff);
+    let p2 = strassen(&add(&a11, &a12), &b22, h, cutoff);
+    let p3 = strassen(&add(&a21, &a22), &b11, h, cutoff);
+    let p4 = strassen(&a22, &sub(&b21, &b11), h, cutoff);
+    let p5 = strassen(&add(&a11, &a22), &add(&b11, &b22), h, cutoff);
+    let p6 = strassen(&sub(&a12, &a22), &add(&b21, &b22), h, cutoff);
+    let p7 = strassen(&sub(&a11, &a21), &add(&b11, &b12), h, cutoff);
+
+    let c11 = add(&sub(&add(&p5, &p4), &p2), &p6);
+    let c12 = add(&p1, &p2);
+    let c21 = add(&p3, &p4);
+    let c22 = sub(&sub(&add(&p1, &p5), &p3), &p7);
+
+    // drop the padding
+    let mut c = vec![T::default(); m * n];
+    for i in 0..m {
+        for j in 0..n {
+            c[i * n + j] = match (i < hm, j < hn) {
+                (true, true) => c11[i * hn + j],
+                (true, false) => c12[i * hn + j - hn],
+                (false, true) => c21[(i - hm) * hn + j],
+                (false, false) => c22[(i - hm) * hn + j - hn],
+            };
+        }
+    }
+    c
+}
+
+// the rows x cols block of the row-major matrix m of the given shape,
+// starting at (row, col), zero padded past its edges
+fn block<T>(m: &[T],
+            (m_rows, m_cols): (usize, usize),
+            (row, col): (usize, usize),
+            (rows, cols): (usize, usize))
+            -> Vec<T>
+    where T: Copy + Default
+{
+    let mut q = vec![T::default(); rows * cols];
+    for i in 0..cmp::min(rows, m_rows - row) {
+        let width = cmp::min(cols, m_cols - col);
+        let start = (row + i) * m_cols + col;
+        q[i * cols..i * cols + width].copy_from_slice(&m[start..start + width]);
+    }
+    q
+}
+
+fn add<T: Copy + Add<Output = T>>(a: &[T], b: &[T]) -> Vec<T> {
+    a.iter().zip(b.iter()).map(|(&x, &y)| x + y).collect()
+}
+
+fn sub<T: Copy + Sub<Output = T>>(a: &[T], b: &[T]) -> Vec<T> {
+    a.iter().zip(b.iter()).map(|(&x, &y)| x - y).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use self::super::*;
+
+    #[test]
+    fn test_naive_mul() {
+        let a = Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]);
+        let b = Matrix::from_vec(3, 2, vec![7, 8, 9, 10, 11, 12]);
+        let c = a.naive_mul(&b);
+        assert_eq!(c, Matrix::from_vec(2, 2, vec![58, 64, 139, 154]));
+        assert_eq!(c.to_string(), "58 64\n139 154\n");
+    }
+
+    #[test]
+    fn test_strassen_mul() {
+        for &(m, k, n) in &[(1, 1, 1), (2, 3, 2), (5, 7, 3), (16, 16, 16), (33, 20, 17), (70, 65, 80)] {
+            let a = Matrix::from_vec(m,
+                                     k,
+                                     (0..m * k).map(|i| ((i * 7919) % 21) as i64 - 10).collect());
+            let b = Matrix::from_vec(k,
+                                     n,
+                                     (0..k * n).map(|i| ((i * 104729) % 17) as i64 - 8).collect());
+            let expected = a.naive_mul(&b);
+            assert_eq!(a.strassen_mul(&b), expected);
+            for &cutoff in &[0, 4, 16] {
+                if m < 64 || cutoff >= 16 {
+                    assert_eq!(a.strassen_mul_with_cutoff(&b, cutoff), expected);
+                }
+            }
+        }
+
+        let a = Matrix::from_vec(2, 2, vec![0.5, 1.0, 1.5, 2.0]);
+        assert_eq!(a.strassen_mul_with_cutoff(&a, 0),
+                   Matrix::from_vec(2, 2, vec![1.75, 2.5, 3.75, 5.5]));
+    }
+
+    // counts multiplications, to check the work done
+    #[derive(Debug, Clone, Copy, Default, PartialEq)]
+    struct Counted(i64);
+
+    thread_local!(static MULS: ::std::cell::Cell<usize> = ::std::cell::Cell::new(0));
+
+    impl Add for Counted {
+        type Output = Counted;
+        fn add(self, other: Counted) -> Counted {
+            Counted(self.0 + other.0)
+        }
+    }
+
+    impl Sub for Counted {
+        type Output = Counted;
+        fn sub(self, other: Counted) -> Counted {
+            Counted(self.0 - other.0)
+        }
+    }
+
+    impl Mul for Counted {
+        type Output = Counted;
+        fn mul(self, other: Counted) -> Counted {
+            MULS.with(|c| c.set(c.get() + 1));
+            Counted(self.0 * other.0)
+        }
+    }
+
+    #[test]
+    fn test_strassen_mul_rectangular() {
+        // thin shapes must not be padded up to a large square
+        for &(m, k, n, cutoff) in &[(1, 1000, 1, 64),
+                                    (1000, 1, 1000, 64),
+                                    (4, 500, 200, 2),
+                                    (64, 1024, 64, 32)] {
+            let a = Matrix::from_vec(m,
+                                     k,
+                                     (0..m * k).map(|i| Counted((i % 7) as i64 - 3)).collect());
+            let b = Matrix::from_vec(k,
+                                     n,
+                                     (0..k * n).map(|i| Counted((i % 5) as i64 - 2)).collect());
+            let expected = a.naive_mul(&b);
+
+            MULS.with(|c| c.set(0));
+            assert_eq!(a.strassen_mul_with_cutoff(&b, cutoff), expected);
+            let muls = MULS.with(|c| c.get());
+            assert!(muls <= m * k * n, "{} multiplications", muls);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_strassen_mul_mismatch() {
+        let a: Matrix<i64> = Matrix::new(2, 3);
+        a.strassen_mul(&Matrix::new(2, 3));
+    }
+}